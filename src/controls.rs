@@ -0,0 +1,376 @@
+//! Rebindable controls.
+//!
+//! Every action the player can trigger has two keyboard or mouse bindings and a gamepad
+//! binding, stored in [`ControlsConfig`]. The config is loaded from `controls.ron` in the save storage
+//! at startup and written back whenever a binding changes in the controls menu.
+//!
+//! Gameplay actions read their bindings when the player's input context is set up. Everything
+//! else asks [`Controls::just_pressed`] or uses the [`control_just_pressed`] run condition.
+//! Escape, the left stick and the mouse wheel are not rebindable.
+
+use std::collections::HashMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_enhanced_input::prelude::Binding;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    save::storage::{SaveBackend, SaveStorage},
+    toast::Toast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ControlsConfig>();
+    app.add_systems(Startup, load_controls);
+}
+
+/// Key of the controls file in the save storage.
+const CONTROLS_KEY: &str = "controls.ron";
+
+/// Something the player can bind an input to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum ControlAction {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Dash,
+    Sprint,
+    Fire,
+    Interact,
+    NextWeapon,
+    PreviousWeapon,
+    /// Select a slot of the weapon bar, from 1 to 9.
+    WeaponSlot(u8),
+    Pause,
+    QuickSave,
+    QuickLoad,
+    TogglePerfUi,
+}
+
+impl ControlAction {
+    /// Every action, in the order the controls menu lists them.
+    pub const ALL: [ControlAction; 22] = [
+        ControlAction::MoveLeft,
+        ControlAction::MoveRight,
+        ControlAction::Jump,
+        ControlAction::Dash,
+        ControlAction::Sprint,
+        ControlAction::Fire,
+        ControlAction::Interact,
+        ControlAction::NextWeapon,
+        ControlAction::PreviousWeapon,
+        ControlAction::Pause,
+        ControlAction::QuickSave,
+        ControlAction::QuickLoad,
+        ControlAction::TogglePerfUi,
+        ControlAction::WeaponSlot(1),
+        ControlAction::WeaponSlot(2),
+        ControlAction::WeaponSlot(3),
+        ControlAction::WeaponSlot(4),
+        ControlAction::WeaponSlot(5),
+        ControlAction::WeaponSlot(6),
+        ControlAction::WeaponSlot(7),
+        ControlAction::WeaponSlot(8),
+        ControlAction::WeaponSlot(9),
+    ];
+
+    pub fn display_name(self) -> String {
+        match self {
+            ControlAction::MoveLeft => "Move left".to_string(),
+            ControlAction::MoveRight => "Move right".to_string(),
+            ControlAction::Jump => "Jump".to_string(),
+            ControlAction::Dash => "Dash".to_string(),
+            ControlAction::Sprint => "Sprint".to_string(),
+            ControlAction::Fire => "Fire".to_string(),
+            ControlAction::Interact => "Interact".to_string(),
+            ControlAction::NextWeapon => "Next weapon".to_string(),
+            ControlAction::PreviousWeapon => "Previous weapon".to_string(),
+            ControlAction::WeaponSlot(slot) => format!("Weapon {}", slot),
+            ControlAction::Pause => "Pause".to_string(),
+            ControlAction::QuickSave => "Quick save".to_string(),
+            ControlAction::QuickLoad => "Quick load".to_string(),
+            ControlAction::TogglePerfUi => "Performance info".to_string(),
+        }
+    }
+
+    fn default_bindings(self) -> ActionBindings {
+        use InputBinding::{Gamepad, Key, Mouse};
+
+        let (keyboard, gamepad) = match self {
+            ControlAction::MoveLeft => (Some(Key(KeyCode::KeyA)), Some(GamepadButton::DPadLeft)),
+            ControlAction::MoveRight => (Some(Key(KeyCode::KeyD)), Some(GamepadButton::DPadRight)),
+            ControlAction::Jump => (Some(Key(KeyCode::Space)), Some(GamepadButton::South)),
+            ControlAction::Dash => (Some(Key(KeyCode::KeyF)), Some(GamepadButton::West)),
+            ControlAction::Sprint => (
+                Some(Key(KeyCode::ShiftLeft)),
+                Some(GamepadButton::LeftThumb),
+            ),
+            ControlAction::Fire => (
+                Some(Mouse(MouseButton::Right)),
+                Some(GamepadButton::RightTrigger2),
+            ),
+            ControlAction::Interact => (Some(Key(KeyCode::KeyE)), Some(GamepadButton::North)),
+            ControlAction::NextWeapon => {
+                (Some(Key(KeyCode::KeyQ)), Some(GamepadButton::RightTrigger))
+            }
+            ControlAction::PreviousWeapon => (None, Some(GamepadButton::LeftTrigger)),
+            ControlAction::WeaponSlot(slot) => (
+                WEAPON_SLOT_KEYS.get(slot as usize - 1).map(|key| Key(*key)),
+                None,
+            ),
+            ControlAction::Pause => (Some(Key(KeyCode::KeyP)), Some(GamepadButton::Start)),
+            ControlAction::QuickSave => (Some(Key(KeyCode::F5)), None),
+            ControlAction::QuickLoad => (Some(Key(KeyCode::F9)), None),
+            ControlAction::TogglePerfUi => (Some(Key(KeyCode::F12)), None),
+        };
+        let alt_keyboard = match self {
+            ControlAction::NextWeapon => Some(Key(KeyCode::Tab)),
+            _ => None,
+        };
+        ActionBindings {
+            keyboard,
+            alt_keyboard,
+            gamepad: gamepad.map(Gamepad),
+        }
+    }
+}
+
+/// Default keys selecting a slot of the weapon bar directly.
+const WEAPON_SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// A single key or button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl InputBinding {
+    pub fn display_name(self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{:?}", key);
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            InputBinding::Mouse(MouseButton::Other(button)) => format!("Mouse {}", button),
+            InputBinding::Mouse(button) => format!("{:?} Mouse", button),
+            InputBinding::Gamepad(button) => match button {
+                GamepadButton::LeftTrigger => "LB".to_string(),
+                GamepadButton::RightTrigger => "RB".to_string(),
+                GamepadButton::LeftTrigger2 => "LT".to_string(),
+                GamepadButton::RightTrigger2 => "RT".to_string(),
+                GamepadButton::LeftThumb => "Left Stick".to_string(),
+                GamepadButton::RightThumb => "Right Stick".to_string(),
+                GamepadButton::DPadUp => "D-Pad Up".to_string(),
+                GamepadButton::DPadDown => "D-Pad Down".to_string(),
+                GamepadButton::DPadLeft => "D-Pad Left".to_string(),
+                GamepadButton::DPadRight => "D-Pad Right".to_string(),
+                button => format!("{:?}", button),
+            },
+        }
+    }
+
+    fn just_pressed(
+        self,
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Query<&Gamepad>,
+    ) -> bool {
+        match self {
+            InputBinding::Key(key) => keys.just_pressed(key),
+            InputBinding::Mouse(button) => mouse.just_pressed(button),
+            InputBinding::Gamepad(button) => {
+                gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+            }
+        }
+    }
+}
+
+impl From<InputBinding> for Binding {
+    fn from(binding: InputBinding) -> Self {
+        match binding {
+            InputBinding::Key(key) => key.into(),
+            InputBinding::Mouse(button) => button.into(),
+            InputBinding::Gamepad(button) => button.into(),
+        }
+    }
+}
+
+/// Which of an action's bindings is meant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum BindingSlot {
+    /// A key or mouse button.
+    Keyboard,
+    /// Another key or mouse button.
+    AltKeyboard,
+    Gamepad,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionBindings {
+    pub keyboard: Option<InputBinding>,
+    /// Missing from configs written before actions had a second key.
+    #[serde(default)]
+    pub alt_keyboard: Option<InputBinding>,
+    pub gamepad: Option<InputBinding>,
+}
+
+impl ActionBindings {
+    pub fn get(&self, slot: BindingSlot) -> Option<InputBinding> {
+        match slot {
+            BindingSlot::Keyboard => self.keyboard,
+            BindingSlot::AltKeyboard => self.alt_keyboard,
+            BindingSlot::Gamepad => self.gamepad,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = InputBinding> {
+        self.keyboard
+            .into_iter()
+            .chain(self.alt_keyboard)
+            .chain(self.gamepad)
+    }
+
+    /// The keyboard, second keyboard and gamepad bindings as enhanced input bindings, with
+    /// [`Binding::None`] for unbound ones.
+    pub fn to_enhanced_input(self) -> [Binding; 3] {
+        [
+            self.keyboard.map_or(Binding::None, Binding::from),
+            self.alt_keyboard.map_or(Binding::None, Binding::from),
+            self.gamepad.map_or(Binding::None, Binding::from),
+        ]
+    }
+}
+
+/// The bindings chosen by the player. Actions without an entry use their defaults.
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ControlsConfig {
+    #[serde(default)]
+    bindings: HashMap<ControlAction, ActionBindings>,
+}
+
+impl ControlsConfig {
+    pub fn get(&self, action: ControlAction) -> ActionBindings {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_bindings())
+    }
+
+    pub fn set(&mut self, action: ControlAction, slot: BindingSlot, binding: Option<InputBinding>) {
+        let mut bindings = self.get(action);
+        match slot {
+            BindingSlot::Keyboard => bindings.keyboard = binding,
+            BindingSlot::AltKeyboard => bindings.alt_keyboard = binding,
+            BindingSlot::Gamepad => bindings.gamepad = binding,
+        }
+        self.bindings.insert(action, bindings);
+    }
+
+    /// Actions with `binding` as one of their bindings. More than one means a conflict.
+    pub fn actions_bound_to(
+        &self,
+        binding: InputBinding,
+    ) -> impl Iterator<Item = ControlAction> + '_ {
+        ControlAction::ALL
+            .into_iter()
+            .filter(move |action| self.get(*action).iter().any(|own| own == binding))
+    }
+
+    /// Write the config to the save storage.
+    pub fn save(&self, storage: &dyn SaveStorage) -> Result<(), String> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        storage
+            .write(CONTROLS_KEY, &contents)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Reads whether a [`ControlAction`] was triggered this frame.
+#[derive(SystemParam)]
+pub struct Controls<'w, 's> {
+    config: Res<'w, ControlsConfig>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl Controls<'_, '_> {
+    pub fn just_pressed(&self, action: ControlAction) -> bool {
+        self.config
+            .get(action)
+            .iter()
+            .any(|binding| binding.just_pressed(&self.keys, &self.mouse, &self.gamepads))
+    }
+}
+
+/// Run condition that is true when one of the bindings of `action` was just pressed.
+pub fn control_just_pressed(action: ControlAction) -> impl FnMut(Controls) -> bool + Clone {
+    move |controls: Controls| controls.just_pressed(action)
+}
+
+fn load_controls(
+    mut commands: Commands,
+    storage: Res<SaveBackend>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let contents = match storage.read(CONTROLS_KEY) {
+        Ok(contents) => contents,
+        // Nothing rebound yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            warn!("Failed to read {}: {}", CONTROLS_KEY, e);
+            return;
+        }
+    };
+
+    match ron::from_str::<ControlsConfig>(&contents) {
+        Ok(config) => {
+            info!("Loaded controls from {}", CONTROLS_KEY);
+            commands.insert_resource(config);
+        }
+        Err(e) => {
+            error!("Failed to parse {}: {}", CONTROLS_KEY, e);
+            toasts.write(Toast::error("Could not load controls, using the defaults"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_and_q_both_cycle_forward() {
+        let config = ControlsConfig::default();
+        let next = config.get(ControlAction::NextWeapon);
+        assert_eq!(next.keyboard, Some(InputBinding::Key(KeyCode::KeyQ)));
+        assert_eq!(next.alt_keyboard, Some(InputBinding::Key(KeyCode::Tab)));
+        assert_eq!(config.get(ControlAction::PreviousWeapon).keyboard, None);
+    }
+
+    #[test]
+    fn loads_configs_without_second_keys() {
+        let config: ControlsConfig =
+            ron::from_str("(bindings: {Jump: (keyboard: Some(Key(KeyW)), gamepad: None)})")
+                .unwrap();
+        let jump = config.get(ControlAction::Jump);
+        assert_eq!(jump.keyboard, Some(InputBinding::Key(KeyCode::KeyW)));
+        assert_eq!(jump.alt_keyboard, None);
+    }
+}
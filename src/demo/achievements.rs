@@ -0,0 +1,310 @@
+//! Achievements unlocked by reaching milestones.
+//!
+//! Achievements are defined in `assets/configurations/achievements.ron` and checked against
+//! [`GameStats`], [`Money`] and [`PlayerUpgrades`]. Unlocked achievements are kept in a profile
+//! next to the save files, so they survive starting over or loading an older save.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{asset::LoadState, prelude::*, ui::Val::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    demo::{
+        player::Money,
+        shop::shop::{PlayerUpgrades, WeaponType},
+        stats::GameStats,
+    },
+    ron_asset::RonAssetApp,
+    save::{
+        integrity,
+        storage::{SaveBackend, SaveStorage},
+    },
+    screens::Screen,
+    theme::palette::{ACHIEVEMENT_BORDER, HEADER_TEXT, LABEL_TEXT, TOAST_BACKGROUND},
+    toast::Toast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_ron_asset::<AchievementsConfig>();
+    app.add_event::<AchievementUnlocked>();
+    app.init_resource::<AchievementPopups>();
+    app.register_type::<AchievementPopup>();
+
+    app.add_systems(Startup, (load_achievements, load_profile));
+    app.add_systems(
+        Update,
+        insert_achievements.run_if(resource_exists::<AchievementsConfigHandle>),
+    );
+    app.add_systems(
+        Update,
+        check_achievements
+            .run_if(resource_exists::<Achievements>)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(Update, (queue_popups, show_popups).chain());
+}
+
+const ACHIEVEMENTS_PATH: &str = "configurations/achievements.ron";
+/// Storage key of the profile holding unlocked achievements.
+const PROFILE_KEY: &str = "profile.ron";
+/// How long an achievement popup stays on screen, in seconds.
+const POPUP_DURATION_SECS: f32 = 4.0;
+
+/// What has to happen to unlock an achievement.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Condition {
+    /// Have at least this much money at once.
+    Money(u32),
+    MoneyEarned(u64),
+    MoneySpent(u64),
+    ItemsPurchased(u32),
+    CoinsCollected(u32),
+    ShotsFired(u32),
+    Hits(u32),
+    /// Hit accuracy between 0 and 1.
+    Accuracy(f32),
+    /// Seconds of play time.
+    PlayTime(f32),
+    BufferLevel(u32),
+    OwnsWeapon(WeaponType),
+    OwnsAllWeapons,
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn is_met(&self, stats: &GameStats, money: &Money, upgrades: &PlayerUpgrades) -> bool {
+        match self {
+            Condition::Money(amount) => money.amount >= *amount,
+            Condition::MoneyEarned(amount) => stats.money_earned >= *amount,
+            Condition::MoneySpent(amount) => stats.money_spent >= *amount,
+            Condition::ItemsPurchased(count) => stats.items_purchased >= *count,
+            Condition::CoinsCollected(count) => stats.coins_collected >= *count,
+            Condition::ShotsFired(count) => stats.total_shots() >= *count,
+            Condition::Hits(count) => stats.hits >= *count,
+            Condition::Accuracy(accuracy) => stats.accuracy() >= *accuracy,
+            Condition::PlayTime(seconds) => stats.total_play_time() >= *seconds,
+            Condition::BufferLevel(level) => upgrades.buffer_level >= *level,
+            Condition::OwnsWeapon(weapon) => upgrades.owns_weapon(*weapon),
+            Condition::OwnsAllWeapons => WeaponType::ALL
+                .iter()
+                .all(|weapon| upgrades.owns_weapon(*weapon)),
+            Condition::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.is_met(stats, money, upgrades)),
+            Condition::Any(conditions) => conditions
+                .iter()
+                .any(|condition| condition.is_met(stats, money, upgrades)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AchievementData {
+    /// Stable identifier stored in the profile. Never change it once released.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: Condition,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Serialize)]
+pub struct AchievementsConfig {
+    pub achievements: Vec<AchievementData>,
+}
+
+/// The achievement definitions while they are loading.
+#[derive(Resource)]
+struct AchievementsConfigHandle(Handle<AchievementsConfig>);
+
+/// All achievements the game knows about.
+#[derive(Resource)]
+pub struct Achievements {
+    pub config: AchievementsConfig,
+}
+
+/// Achievements the player has unlocked, in the order they were unlocked.
+#[derive(Resource, Default, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    pub unlocked: Vec<String>,
+}
+
+impl Profile {
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == id)
+    }
+}
+
+/// An achievement was unlocked just now.
+#[derive(Event, Clone, Debug)]
+pub struct AchievementUnlocked {
+    pub name: String,
+    pub description: String,
+}
+
+fn load_achievements(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AchievementsConfigHandle(
+        asset_server.load(ACHIEVEMENTS_PATH),
+    ));
+}
+
+/// Insert the [`Achievements`] once their definitions finished loading.
+fn insert_achievements(
+    mut commands: Commands,
+    handle: Res<AchievementsConfigHandle>,
+    asset_server: Res<AssetServer>,
+    mut configs: ResMut<Assets<AchievementsConfig>>,
+    mut toasts: EventWriter<Toast>,
+) {
+    match asset_server.load_state(&handle.0) {
+        LoadState::Loaded => {
+            if let Some(config) = configs.remove(&handle.0) {
+                info!("Loaded {} achievements", config.achievements.len());
+                commands.insert_resource(Achievements { config });
+            }
+        }
+        LoadState::Failed(e) => {
+            error!("Failed to load achievements.ron: {}", e);
+            toasts.write(Toast::error("Achievements could not be loaded"));
+        }
+        _ => return,
+    }
+    commands.remove_resource::<AchievementsConfigHandle>();
+}
+
+fn load_profile(mut commands: Commands, storage: Res<SaveBackend>) {
+    let profile = if storage.exists(PROFILE_KEY) {
+        integrity::read_verified(&**storage, PROFILE_KEY)
+            .and_then(|data| Ok(serde_json::from_value::<Profile>(data)?))
+            .unwrap_or_else(|e| {
+                error!("Failed to load profile, achievements start locked: {}", e);
+                Profile::default()
+            })
+    } else {
+        Profile::default()
+    };
+    commands.insert_resource(profile);
+}
+
+fn save_profile(storage: &dyn SaveStorage, profile: &Profile) {
+    let data = match serde_json::to_value(profile) {
+        Ok(data) => data,
+        Err(e) => {
+            error!("Failed to serialize profile: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = integrity::write_with_backup(storage, PROFILE_KEY, &integrity::encode(&data)) {
+        error!("Failed to save profile: {}", e);
+    }
+}
+
+fn check_achievements(
+    achievements: Res<Achievements>,
+    mut profile: ResMut<Profile>,
+    stats: Res<GameStats>,
+    money: Res<Money>,
+    upgrades: Res<PlayerUpgrades>,
+    storage: Res<SaveBackend>,
+    mut unlocked: EventWriter<AchievementUnlocked>,
+) {
+    let mut changed = false;
+    for achievement in &achievements.config.achievements {
+        if profile.is_unlocked(&achievement.id)
+            || !achievement.condition.is_met(&stats, &money, &upgrades)
+        {
+            continue;
+        }
+
+        info!("Achievement unlocked: {}", achievement.name);
+        profile.unlocked.push(achievement.id.clone());
+        unlocked.write(AchievementUnlocked {
+            name: achievement.name.clone(),
+            description: achievement.description.clone(),
+        });
+        changed = true;
+    }
+
+    if changed {
+        save_profile(&**storage, &profile);
+    }
+}
+
+/// Achievements waiting to be announced. Popups are shown one at a time.
+#[derive(Resource, Default)]
+struct AchievementPopups(VecDeque<AchievementUnlocked>);
+
+/// The popup announcing an achievement, with the time it has left on screen.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AchievementPopup(Timer);
+
+fn queue_popups(
+    mut unlocked: EventReader<AchievementUnlocked>,
+    mut popups: ResMut<AchievementPopups>,
+) {
+    popups.0.extend(unlocked.read().cloned());
+}
+
+fn show_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popups: ResMut<AchievementPopups>,
+    mut popup_query: Query<(Entity, &mut AchievementPopup)>,
+) {
+    if let Ok((entity, mut popup)) = popup_query.single_mut() {
+        popup.0.tick(time.delta());
+        if popup.0.finished() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let Some(achievement) = popups.0.pop_front() else {
+        return;
+    };
+    commands.spawn((
+        Name::new("Achievement Popup"),
+        AchievementPopup(Timer::new(
+            Duration::from_secs_f32(POPUP_DURATION_SECS),
+            TimerMode::Once,
+        )),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(16.0),
+            left: Percent(50.0),
+            width: Px(360.0),
+            margin: UiRect::left(Px(-180.0)),
+            padding: UiRect::all(Px(12.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(4.0),
+            border: UiRect::all(Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(TOAST_BACKGROUND),
+        BorderColor(ACHIEVEMENT_BORDER),
+        BorderRadius::all(Px(8.0)),
+        // Above menus and the pause overlay, like toasts.
+        GlobalZIndex(10),
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("Achievement Title"),
+                Text(format!("Achievement unlocked: {}", achievement.name)),
+                TextFont::from_font_size(22.0),
+                TextColor(HEADER_TEXT),
+                Pickable::IGNORE,
+            ),
+            (
+                Name::new("Achievement Description"),
+                Text(achievement.description),
+                TextFont::from_font_size(18.0),
+                TextColor(LABEL_TEXT),
+                Pickable::IGNORE,
+            ),
+        ],
+    ));
+}
@@ -0,0 +1,85 @@
+//! The camera following the player through levels wider than the screen.
+//!
+//! The player can move freely inside a dead zone around the middle of the screen. Beyond it the
+//! camera catches up smoothly, without ever showing anything past the level edges.
+
+use bevy::{math::StableInterpolate, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        level::{Background, LevelBounds, visible_width},
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera);
+    app.add_systems(
+        Update,
+        (
+            follow_player.in_set(PausableSystems),
+            scroll_background_layers,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How far the player can get from the middle of the screen before the camera follows.
+const DEAD_ZONE: f32 = 80.0;
+/// How quickly the camera catches up. Higher is snappier.
+const FOLLOW_DECAY_RATE: f32 = 6.0;
+
+/// Put the camera back in the middle for the title screen.
+fn reset_camera(mut camera: Single<&mut Transform, With<Camera2d>>) {
+    camera.translation.x = 0.0;
+}
+
+fn follow_player(
+    time: Res<Time>,
+    bounds: Res<LevelBounds>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    player: Single<&Transform, (With<Player>, Without<Camera2d>)>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+) {
+    let camera_x = camera.translation.x;
+    let player_x = player.translation.x;
+    let mut target = camera_x;
+    if player_x > camera_x + DEAD_ZONE {
+        target = player_x - DEAD_ZONE;
+    } else if player_x < camera_x - DEAD_ZONE {
+        target = player_x + DEAD_ZONE;
+    }
+
+    let max_x = max_camera_x(&bounds, &window);
+    let mut x = camera_x;
+    x.smooth_nudge(&target, FOLLOW_DECAY_RATE, time.delta_secs());
+    camera.translation.x = x.clamp(-max_x, max_x);
+}
+
+/// Move background layers with the camera according to their parallax, and make them wide
+/// enough to cover everything the camera can show.
+fn scroll_background_layers(
+    bounds: Res<LevelBounds>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<&Transform, With<Camera2d>>,
+    mut layer_query: Query<(&Background, &mut Transform, &mut Sprite), Without<Camera2d>>,
+) {
+    let view_width = visible_width(&window);
+    let max_x = max_camera_x(&bounds, &window);
+    for (layer, mut transform, mut sprite) in &mut layer_query {
+        transform.translation.x = camera.translation.x * layer.parallax;
+        sprite.custom_size = Some(Vec2::new(
+            view_width + 2.0 * max_x * (1.0 - layer.parallax),
+            600.0,
+        ));
+    }
+}
+
+/// How far the camera can move from the middle of the level in either direction.
+fn max_camera_x(bounds: &LevelBounds, window: &Window) -> f32 {
+    bounds.half_width(window) - visible_width(window) / 2.0
+}
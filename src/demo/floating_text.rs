@@ -0,0 +1,150 @@
+//! Numbers floating up from hits and coin pickups.
+//!
+//! Hits and pickups happen many times per second with the faster weapons, so the text
+//! entities are spawned once per gameplay session and reused.
+
+use bevy::{prelude::*, text::FontSmoothing};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        player::Player,
+        stats::{CoinCollected, TargetHit},
+    },
+    screens::Screen,
+    theme::palette::{FLOATING_COIN_TEXT, FLOATING_DAMAGE_TEXT},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FloatingText>();
+    app.init_resource::<FloatingTextSettings>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_floating_text_pool);
+    app.add_systems(
+        Update,
+        (show_floating_text, animate_floating_text)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Number of pooled text entities. When all are in use, the oldest one is reused.
+const POOL_SIZE: usize = 48;
+/// Seconds a number stays on screen.
+const LIFETIME_SECS: f32 = 0.8;
+/// How fast numbers rise, in world units per second.
+const RISE_SPEED: f32 = 60.0;
+
+#[derive(Resource)]
+pub struct FloatingTextSettings {
+    pub enabled: bool,
+}
+
+impl Default for FloatingTextSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// A pooled floating number. Hidden while not in use.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct FloatingText {
+    /// Seconds left on screen, zero when unused.
+    remaining: f32,
+}
+
+fn spawn_floating_text_pool(mut commands: Commands) {
+    for _ in 0..POOL_SIZE {
+        commands.spawn((
+            Name::new("Floating Text"),
+            FloatingText::default(),
+            Text2d::default(),
+            TextFont::from_font_size(20.0).with_font_smoothing(FontSmoothing::None),
+            TextColor(FLOATING_DAMAGE_TEXT),
+            Transform::default(),
+            Visibility::Hidden,
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn show_floating_text(
+    settings: Res<FloatingTextSettings>,
+    mut hits: EventReader<TargetHit>,
+    mut collected: EventReader<CoinCollected>,
+    player: Query<&Transform, (With<Player>, Without<FloatingText>)>,
+    mut pool: Query<(
+        &mut FloatingText,
+        &mut Text2d,
+        &mut TextColor,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    if !settings.enabled {
+        hits.clear();
+        collected.clear();
+        return;
+    }
+
+    let player_position = player
+        .single()
+        .map(|transform| transform.translation.truncate())
+        .unwrap_or_default();
+    let popups = hits
+        .read()
+        .map(|hit| (hit.damage.to_string(), hit.position, FLOATING_DAMAGE_TEXT))
+        .chain(collected.read().map(|coin| {
+            (
+                format!("+${}", coin.value),
+                player_position + Vec2::Y * 32.0,
+                FLOATING_COIN_TEXT,
+            )
+        }))
+        .collect::<Vec<_>>();
+
+    for (text, position, color) in popups {
+        // Take the entry closest to expiring, which is a free one if there is any
+        let Some((mut floating, mut text2d, mut text_color, mut transform, mut visibility)) = pool
+            .iter_mut()
+            .min_by(|a, b| a.0.remaining.total_cmp(&b.0.remaining))
+        else {
+            return;
+        };
+
+        floating.remaining = LIFETIME_SECS;
+        text2d.0 = text;
+        text_color.0 = color;
+        transform.translation = position.extend(20.0);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn animate_floating_text(
+    time: Res<Time>,
+    mut pool: Query<(
+        &mut FloatingText,
+        &mut TextColor,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    let delta = time.delta_secs();
+    for (mut floating, mut color, mut transform, mut visibility) in &mut pool {
+        if floating.remaining <= 0.0 {
+            continue;
+        }
+
+        floating.remaining -= delta;
+        if floating.remaining <= 0.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        transform.translation.y += RISE_SPEED * delta;
+        color.0.set_alpha(floating.remaining / LIFETIME_SECS);
+    }
+}
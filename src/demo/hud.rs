@@ -1,13 +1,33 @@
 //! Simple HUD system
 
-use crate::{AppSystems, PausableSystems, demo::shop::shop::PlayerUpgrades, screens::Screen};
+use std::collections::VecDeque;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        modifiers::{Stat, Stats},
+        player::{Money, Player},
+        stats::CoinCollected,
+        target::{FocusedTarget, Health},
+    },
+    screens::Screen,
+    theme::palette::{
+        BUFFER_BAR_BACKGROUND, BUFFER_BAR_FILL, HUD_TEXT, HUD_TIMER_TEXT, INCOME_TEXT,
+        MONEY_SPENT_FLASH,
+    },
+};
 use bevy::prelude::*;
 use bevy::text::FontSmoothing;
+use bevy::ui::Val::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<GameTimer>();
+    app.init_resource::<IncomeTracker>();
     app.insert_resource(CoinBuffer::new());
-    app.add_systems(OnEnter(Screen::Gameplay), (spawn_hud, start_game_timer));
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (spawn_hud, start_game_timer, reset_income_tracker),
+    );
     app.add_systems(
         Update,
         (
@@ -15,6 +35,9 @@ pub(super) fn plugin(app: &mut App) {
             update_coin_buffer,
             update_buffer_display,
             update_buffer_count_text,
+            update_money_display,
+            (track_income, update_income_display).chain(),
+            update_health_text,
         )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
@@ -27,6 +50,49 @@ pub struct GameHud;
 #[derive(Component)]
 pub struct ScoreText;
 
+/// The money shown by the [`ScoreText`], which catches up with [`Money`] over a short time.
+#[derive(Component, Default)]
+pub struct MoneyCounter {
+    /// `None` until the counter has been shown once, so it doesn't count up from zero.
+    shown: Option<f32>,
+    /// The amount of money last frame, to notice spending.
+    last_amount: u32,
+    /// Seconds left of the flash after spending money.
+    flash: f32,
+}
+
+#[derive(Component)]
+pub struct IncomeText;
+
+/// Money collected recently, for the income readout.
+#[derive(Resource, Default)]
+pub struct IncomeTracker {
+    /// Gameplay time of each pickup and its value, oldest first.
+    pickups: VecDeque<(f32, u32)>,
+    /// Seconds of unpaused gameplay since the tracker was reset.
+    elapsed: f32,
+}
+
+impl IncomeTracker {
+    /// Longest window income is averaged over, in seconds.
+    const WINDOW_SECS: f32 = 60.0;
+
+    /// Average income per second over the last `window` seconds.
+    pub fn per_second(&self, window: f32) -> f32 {
+        let window = window.min(Self::WINDOW_SECS);
+        let since = self.elapsed - window;
+        let total: u32 = self
+            .pickups
+            .iter()
+            .rev()
+            .take_while(|(time, _)| *time >= since)
+            .map(|(_, value)| value)
+            .sum();
+        // Don't extrapolate from the first few seconds of a session
+        total as f32 / window.min(self.elapsed).max(1.0)
+    }
+}
+
 #[derive(Component)]
 pub struct HealthText;
 
@@ -79,92 +145,146 @@ impl CoinBuffer {
 }
 
 /// Spawns the HUD when entering gameplay
+///
+/// The HUD is a strip along the bottom edge of the screen, so it follows the window size and
+/// the UI scale instead of the camera.
 fn spawn_hud(mut commands: Commands) {
-    // Left text
     commands.spawn((
-        Name::new("Coin Text"),
+        Name::new("HUD"),
         GameHud,
-        ScoreText,
-        Text2d::new("Money: 0"),
-        TextFont {
-            font_size: 32.0,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Px(0.0),
+            width: Percent(100.0),
+            padding: UiRect::axes(Px(24.0), Px(16.0)),
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::fr(3, 1.0),
+            align_items: AlignItems::End,
             ..default()
-        }
-        .with_font_smoothing(FontSmoothing::None),
-        TextColor(Color::WHITE),
-        TextLayout::new_with_justify(JustifyText::Center),
-        Transform::from_translation(Vec3::new(-200.0, -280.0, 10.0)),
+        },
+        Pickable::IGNORE,
         StateScoped(Screen::Gameplay),
+        children![money_text(), time_text(), buffer_bar()],
     ));
 
-    // Center text
     commands.spawn((
-        Name::new("Time Text"),
+        Name::new("Health Text"),
         GameHud,
-        TimeText,
-        Text2d::new("00:00"),
-        TextFont {
-            font_size: 28.0,
+        HealthText,
+        hud_text("", 22.0, HUD_TEXT),
+        // Top left, clear of the achievement popups in the top center
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(16.0),
+            left: Px(24.0),
             ..default()
-        }
-        .with_font_smoothing(FontSmoothing::None),
-        TextColor(Color::srgb(0.8, 0.2, 0.2)),
-        TextLayout::new_with_justify(JustifyText::Center),
-        Transform::from_translation(Vec3::new(0.0, -280.0, 10.0)),
+        },
         StateScoped(Screen::Gameplay),
     ));
+}
 
-    // Buffer Bar (right side)
-    commands
-        .spawn((
-            Name::new("Buffer Bar Container"),
-            GameHud,
-            BufferBar,
-            Sprite::from_color(Color::srgb(0.3, 0.3, 0.3), Vec2::new(120.0, 20.0)),
-            Transform::from_translation(Vec3::new(200.0, -280.0, 10.0)),
-            StateScoped(Screen::Gameplay),
-        ))
-        .with_children(|parent| {
-            // Buffer bar fill
-            parent.spawn((
-                Name::new("Buffer Bar Fill"),
-                BufferBarFill,
-                Sprite::from_color(Color::srgb(0.0, 0.8, 0.2), Vec2::new(0.0, 18.0)),
-                Transform::from_translation(Vec3::new(-60.0, 0.0, 1.0)),
-            ));
-
-            // Buffer count text inside the bar
-            parent.spawn((
-                Name::new("Buffer Count Text"),
-                BufferCountText,
-                Text2d::new("0/20"),
-                TextFont {
-                    font_size: 14.0,
-                    ..default()
-                }
-                .with_font_smoothing(FontSmoothing::None),
-                TextColor(Color::WHITE),
-                TextLayout::new_with_justify(JustifyText::Center),
-                Transform::from_translation(Vec3::new(0.0, 0.0, 2.0)),
-            ));
-        });
+fn hud_text(text: impl Into<String>, font_size: f32, color: Color) -> impl Bundle {
+    (
+        Text(text.into()),
+        TextFont::from_font_size(font_size).with_font_smoothing(FontSmoothing::None),
+        TextColor(color),
+        Pickable::IGNORE,
+    )
+}
 
-    commands.spawn((
-        Name::new("Buffer Text Label"),
-        GameHud,
-        Text2d::new("Buffer"),
-        TextFont {
-            font_size: 18.0,
+fn money_text() -> impl Bundle {
+    (
+        Name::new("Money"),
+        Node {
+            justify_self: JustifySelf::Start,
+            flex_direction: FlexDirection::Column,
             ..default()
-        }
-        .with_font_smoothing(FontSmoothing::None),
-        TextColor(Color::WHITE),
-        TextLayout::new_with_justify(JustifyText::Center),
-        Transform::from_translation(Vec3::new(200.0, -250.0, 10.0)),
-        StateScoped(Screen::Gameplay),
-    ));
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("Coin Text"),
+                ScoreText,
+                MoneyCounter::default(),
+                hud_text("Money: $0", 32.0, HUD_TEXT),
+            ),
+            (
+                Name::new("Income Text"),
+                IncomeText,
+                hud_text("", 16.0, INCOME_TEXT),
+            ),
+        ],
+    )
+}
+
+fn time_text() -> impl Bundle {
+    (
+        Name::new("Time Text"),
+        TimeText,
+        hud_text("00:00", 28.0, HUD_TIMER_TEXT),
+        Node {
+            justify_self: JustifySelf::Center,
+            ..default()
+        },
+    )
+}
+
+fn buffer_bar() -> impl Bundle {
+    (
+        Name::new("Buffer"),
+        Node {
+            justify_self: JustifySelf::End,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(4.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("Buffer Text Label"),
+                hud_text("Buffer", 18.0, HUD_TEXT)
+            ),
+            (
+                Name::new("Buffer Bar Container"),
+                BufferBar,
+                Node {
+                    width: Px(BUFFER_BAR_WIDTH),
+                    height: Px(20.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(BUFFER_BAR_BACKGROUND),
+                Pickable::IGNORE,
+                children![
+                    (
+                        Name::new("Buffer Bar Fill"),
+                        BufferBarFill,
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Px(1.0),
+                            top: Px(1.0),
+                            bottom: Px(1.0),
+                            width: Percent(0.0),
+                            ..default()
+                        },
+                        BackgroundColor(BUFFER_BAR_FILL),
+                        Pickable::IGNORE,
+                    ),
+                    (
+                        Name::new("Buffer Count Text"),
+                        BufferCountText,
+                        hud_text("0/20", 14.0, HUD_TEXT),
+                    ),
+                ],
+            ),
+        ],
+    )
 }
 
+const BUFFER_BAR_WIDTH: f32 = 120.0;
+
 /// Start the game timer when entering gameplay
 fn start_game_timer(mut timer: ResMut<GameTimer>) {
     timer.elapsed = 0.0;
@@ -174,7 +294,7 @@ fn start_game_timer(mut timer: ResMut<GameTimer>) {
 fn update_timer_display(
     time: Res<Time>,
     mut timer: ResMut<GameTimer>,
-    mut text_query: Query<&mut Text2d, With<TimeText>>,
+    mut text_query: Query<&mut Text, With<TimeText>>,
 ) {
     timer.elapsed += time.delta_secs();
 
@@ -190,15 +310,15 @@ fn update_timer_display(
 fn update_coin_buffer(
     time: Res<Time>,
     mut buffer: ResMut<CoinBuffer>,
-    upgrades: Res<PlayerUpgrades>,
+    stats: Single<&Stats, With<Player>>,
 ) {
-    let new_max = 20.0 + (upgrades.buffer_level as f32 * 50.0);
+    let new_max = stats.get(Stat::BufferCapacity);
     if buffer.max != new_max {
         buffer.max = new_max;
     }
 
     if buffer.current > 0.0 {
-        let drain_rate = buffer.max * 0.1 * upgrades.buffer_level as f32;
+        let drain_rate = buffer.max * stats.get(Stat::BufferDrain);
         buffer.drain(time.delta_secs() * drain_rate);
     }
 }
@@ -206,17 +326,14 @@ fn update_coin_buffer(
 /// Update the buffer bar fill
 fn update_buffer_display(
     buffer: Res<CoinBuffer>,
-    mut bar_query: Query<(&mut Sprite, &mut Transform), With<BufferBarFill>>,
+    mut bar_query: Query<&mut Node, With<BufferBarFill>>,
 ) {
     if buffer.is_changed() {
-        let percentage = buffer.get_percentage();
-        let max_width = 120.0;
-        let fill_width = max_width * percentage;
-
-        for (mut sprite, mut transform) in &mut bar_query {
-            sprite.custom_size = Some(Vec2::new(fill_width, 18.0));
-            let offset_x = -60.0 + (fill_width / 2.0);
-            transform.translation.x = offset_x;
+        // Leave room for the 1px border on either side
+        let fill_width = (BUFFER_BAR_WIDTH - 2.0) * buffer.get_percentage();
+
+        for mut node in &mut bar_query {
+            node.width = Px(fill_width);
         }
     }
 }
@@ -224,7 +341,7 @@ fn update_buffer_display(
 /// Update the buffer count text
 fn update_buffer_count_text(
     buffer: Res<CoinBuffer>,
-    mut text_query: Query<&mut Text2d, With<BufferCountText>>,
+    mut text_query: Query<&mut Text, With<BufferCountText>>,
 ) {
     if buffer.is_changed() {
         let current = buffer.current as u32;
@@ -235,3 +352,104 @@ fn update_buffer_count_text(
         }
     }
 }
+
+/// Tick the money counter towards the current amount, flashing when money is spent
+fn update_money_display(
+    time: Res<Time>,
+    money: Res<Money>,
+    mut text_query: Query<(&mut Text, &mut TextColor, &mut MoneyCounter), With<ScoreText>>,
+) {
+    /// How quickly the counter catches up; higher is faster.
+    const CATCH_UP_RATE: f32 = 8.0;
+    const FLASH_SECS: f32 = 0.4;
+
+    let target = money.amount as f32;
+    for (mut text, mut color, mut counter) in &mut text_query {
+        let shown = match counter.shown {
+            Some(shown) => {
+                if money.amount < counter.last_amount {
+                    counter.flash = FLASH_SECS;
+                }
+                let shown =
+                    shown + (target - shown) * (1.0 - (-CATCH_UP_RATE * time.delta_secs()).exp());
+                if (target - shown).abs() < 0.5 {
+                    target
+                } else {
+                    shown
+                }
+            }
+            None => target,
+        };
+        counter.last_amount = money.amount;
+
+        if counter.shown != Some(shown) {
+            counter.shown = Some(shown);
+            text.0 = format!("Money: ${}", shown.round() as u32);
+        }
+
+        if counter.flash > 0.0 {
+            counter.flash = (counter.flash - time.delta_secs()).max(0.0);
+            color.0 = HUD_TEXT.mix(&MONEY_SPENT_FLASH, counter.flash / FLASH_SECS);
+        }
+    }
+}
+
+fn reset_income_tracker(mut tracker: ResMut<IncomeTracker>) {
+    *tracker = IncomeTracker::default();
+}
+
+fn track_income(
+    time: Res<Time>,
+    mut collected: EventReader<CoinCollected>,
+    mut tracker: ResMut<IncomeTracker>,
+) {
+    tracker.elapsed += time.delta_secs();
+    let now = tracker.elapsed;
+    for coin in collected.read() {
+        tracker.pickups.push_back((now, coin.value));
+    }
+
+    while tracker
+        .pickups
+        .front()
+        .is_some_and(|(time, _)| *time < now - IncomeTracker::WINDOW_SECS)
+    {
+        tracker.pickups.pop_front();
+    }
+}
+
+fn update_income_display(
+    tracker: Res<IncomeTracker>,
+    mut text_query: Query<&mut Text, With<IncomeText>>,
+) {
+    let per_second = tracker.per_second(10.0);
+    let per_minute = tracker.per_second(IncomeTracker::WINDOW_SECS) * 60.0;
+    let income = format!("${:.1}/s  ${:.0}/min", per_second, per_minute);
+    for mut text in &mut text_query {
+        if text.0 != income {
+            text.0 = income.clone();
+        }
+    }
+}
+
+/// Show the health of the target the player is currently damaging
+fn update_health_text(
+    focused: Res<FocusedTarget>,
+    target_query: Query<(&Name, &Health)>,
+    mut text_query: Query<&mut Text, With<HealthText>>,
+) {
+    let health = match focused.0.and_then(|target| target_query.get(target).ok()) {
+        Some((name, health)) => format!(
+            "{}: {} / {}",
+            name,
+            health.current.ceil() as u32,
+            health.max as u32
+        ),
+        None => String::new(),
+    };
+    for mut text in &mut text_query {
+        if text.0 != health {
+            text.0 = health.clone();
+        }
+    }
+}
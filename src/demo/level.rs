@@ -8,22 +8,58 @@ use bevy::window::PrimaryWindow;
 use crate::{
     AppSystems, PausableSystems,
     asset_tracking::LoadResource,
-    demo::player::{CoinBoxMaterial, PlayerAssets, Target, player},
+    audio,
+    demo::{
+        level_description::{EntityKind, LevelDescription, WallSide, color},
+        levels::{CurrentLevel, CurrentLevelAssets, LevelRegistry},
+        player::{CoinBoxMaterial, PlayerAssets, Target, player},
+        target::Health,
+    },
     screens::Screen,
+    toast::Toast,
 };
 
+/// Collision layer of the ground, which the player and coins land on.
+///
+/// Kept off the default layer, so sensors like shops and the coin box don't count as ground.
+pub const GROUND_LAYER: LayerMask = LayerMask(0b10000);
+/// Collision layer of the level walls, which stop the player and coins.
+pub const WALL_LAYER: LayerMask = LayerMask(0b0100);
+/// Collision layer of platforms. Coins land on them from any side, the player only from above.
+pub const PLATFORM_LAYER: LayerMask = LayerMask(0b1000);
+const PLATFORM_THICKNESS: f32 = 12.0;
+const WALL_THICKNESS: f32 = 100.0;
+const WALL_HEIGHT: f32 = 2000.0;
+
+/// Where the player starts in levels that don't say otherwise.
+const DEFAULT_PLAYER_START: Vec2 = Vec2::new(0.0, -216.0);
+
 #[derive(Component)]
 pub struct Level;
 
 #[derive(Component)]
 pub struct InvisibleWall;
 
+/// A background layer, scrolled by [`camera`](super::camera) for parallax.
 #[derive(Component)]
-pub struct Background;
+pub struct Background {
+    /// From 0 (scrolls with the level) to 1 (stays put on screen).
+    pub parallax: f32,
+}
 
 #[derive(Component)]
 pub struct ShopSprite;
 
+/// Ground the player and coins land on.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Ground;
+
+/// A platform the player can jump onto from below.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct OneWayPlatform;
+
 #[derive(Component)]
 pub struct LeftWall;
 
@@ -40,27 +76,46 @@ pub struct UpgradeShop;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<LevelAssets>();
+    app.register_type::<Ground>();
+    app.register_type::<OneWayPlatform>();
     app.load_resource::<LevelAssets>();
 
     app.add_plugins(Material2dPlugin::<CoinBoxMaterial>::default());
+    app.init_resource::<LevelBounds>();
 
     app.add_systems(
         Update,
-        (
-            position_invisible_walls,
-            update_background_size,
-            update_shop_sprite_size,
-        )
+        (position_invisible_walls, update_shop_sprite_size)
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
 }
 
+/// Horizontal extent of the current level.
+#[derive(Resource, Default)]
+pub struct LevelBounds {
+    pub width: Option<f32>,
+}
+
+impl LevelBounds {
+    /// Half the width of the level, never less than half the visible area.
+    pub fn half_width(&self, window: &Window) -> f32 {
+        let half_view = visible_width(window) / 2.0;
+        self.width
+            .map_or(half_view, |width| (width / 2.0).max(half_view))
+    }
+}
+
+/// Width of the world area shown by the camera, which uses FixedVertical scaling with 600px
+/// height.
+pub fn visible_width(window: &Window) -> f32 {
+    600.0 * window.width() / window.height()
+}
+
+/// Assets shared by all levels. Level specific assets are listed in the level description.
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct LevelAssets {
-    #[dependency]
-    background: Handle<Image>,
     #[dependency]
     weapon_shop: Handle<Image>,
     #[dependency]
@@ -69,33 +124,34 @@ pub struct LevelAssets {
     coin_box: Handle<Image>,
     #[dependency]
     pub coin: Handle<Image>,
-    pub ground_level: f32,
 }
 
 impl FromWorld for LevelAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
         Self {
-            background: assets.load("myBackground.exr"),
             weapon_shop: assets.load("WeaponShop.exr"),
             upgrade_shop: assets.load("UpgradeShop.exr"),
             coin_box: assets.load("CoinBox.exr"),
             coin: assets.load("Coin.exr"),
-            ground_level: -250.0,
         }
     }
 }
 
-/// A system that spawns the main level.
+/// A system that spawns the current level from its description.
 pub fn spawn_level(
     mut commands: Commands,
     level_assets: Res<LevelAssets>,
     player_assets: Res<PlayerAssets>,
+    registry: Res<LevelRegistry>,
+    current_level: Res<State<CurrentLevel>>,
+    current_level_assets: Res<CurrentLevelAssets>,
+    descriptions: Res<Assets<LevelDescription>>,
     window_query: Query<&Window, With<PrimaryWindow>>,
     existing_level_query: Query<(), With<Level>>,
-    _texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<CoinBoxMaterial>>,
+    mut toasts: EventWriter<Toast>,
 ) {
     // Don't spawn level if it already exists
     if !existing_level_query.is_empty() {
@@ -106,75 +162,163 @@ pub fn spawn_level(
         return; // Skip if window not ready yet
     };
 
-    commands.spawn((
-        Name::new("Level"),
-        Level,
-        Transform::default(),
-        Visibility::default(),
-        StateScoped(Screen::Gameplay),
-        children![
-            background(&level_assets, window),
-            player(400.0, &player_assets),
-            coin_box(&level_assets, &mut meshes, &mut materials),
-            shop_box_upgrades(&level_assets),
-            shop_box_weapons(&level_assets),
-            // Invisible ground for both player and coins
-            invisible_ground(&level_assets),
-            // Invisible walls
-            left_wall(),
-            right_wall(),
-            // (
-            //     Name::new("Gameplay Music"),
-            //     music(level_assets.music.clone())
-            // )
-        ],
-    ));
-}
-
-/// System that positions invisible walls when first spawned
-fn position_invisible_walls(
-    mut left_wall_query: Query<
-        &mut Transform,
-        (With<LeftWall>, Added<LeftWall>, Without<RightWall>),
-    >,
-    mut right_wall_query: Query<
-        &mut Transform,
-        (With<RightWall>, Added<RightWall>, Without<LeftWall>),
-    >,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-) {
-    let Ok(window) = window_query.single() else {
-        return; // Skip if window not ready yet
+    let level = registry.get(current_level.0);
+    info!("Entering {}", level.name);
+
+    let level_entity = commands
+        .spawn((
+            Name::new(format!("Level: {}", level.name)),
+            Level,
+            Transform::default(),
+            Visibility::default(),
+            StateScoped(Screen::Gameplay),
+        ))
+        .id();
+
+    let Some(description) = descriptions.get(&current_level_assets.description) else {
+        error!("Level file {} could not be loaded", level.level);
+        toasts.write(Toast::error(format!("{} could not be loaded", level.name)));
+        commands.insert_resource(LevelBounds::default());
+        commands
+            .entity(level_entity)
+            .with_child(player(&player_assets, DEFAULT_PLAYER_START));
+        return;
     };
-    let window_aspect = window.width() / window.height();
-    let viewport_height = 600.0;
-    let viewport_width = viewport_height * window_aspect;
-    let half_width = viewport_width / 2.0;
 
-    for mut transform in &mut left_wall_query {
-        transform.translation.x = -half_width + 50.0;
+    commands.insert_resource(LevelBounds {
+        width: description.width,
+    });
+
+    let mut has_player = false;
+    for entity in &description.entities {
+        let name = entity.name();
+        let translation = entity.translation();
+        let mut parent = commands.entity(level_entity);
+        match &entity.kind {
+            EntityKind::Background {
+                image,
+                tint,
+                parallax,
+            } => {
+                parent.with_child((
+                    name,
+                    Background {
+                        parallax: *parallax,
+                    },
+                    background(description.image(image), color(*tint), window),
+                    // Behind everything else
+                    Transform::from_translation(translation + Vec3::new(0.0, 0.0, -10.0)),
+                ));
+            }
+            EntityKind::PlayerStart => {
+                has_player = true;
+                parent.with_child(player(&player_assets, translation.truncate()));
+            }
+            EntityKind::Target { health, size } => {
+                parent.with_child((
+                    name,
+                    coin_box(
+                        &level_assets,
+                        *health,
+                        Vec2::new(size.0, size.1),
+                        &mut meshes,
+                        &mut materials,
+                    ),
+                    Transform::from_translation(translation),
+                ));
+            }
+            EntityKind::WeaponShop => {
+                parent.with_child((
+                    name,
+                    WeaponShop,
+                    shop_box(level_assets.weapon_shop.clone()),
+                    Transform::from_translation(translation),
+                ));
+            }
+            EntityKind::UpgradeShop => {
+                parent.with_child((
+                    name,
+                    UpgradeShop,
+                    shop_box(level_assets.upgrade_shop.clone()),
+                    Transform::from_translation(translation),
+                ));
+            }
+            EntityKind::Ground { width } => {
+                parent.with_child((
+                    name,
+                    invisible_ground(*width),
+                    Transform::from_translation(translation),
+                ));
+            }
+            EntityKind::Platform {
+                width,
+                color: platform_color,
+            } => {
+                parent.with_child((
+                    name,
+                    platform(*width, color(*platform_color)),
+                    Transform::from_translation(translation),
+                ));
+            }
+            EntityKind::Wall(WallSide::Left) => {
+                parent.with_child((name, LeftWall, invisible_wall()));
+            }
+            EntityKind::Wall(WallSide::Right) => {
+                parent.with_child((name, RightWall, invisible_wall()));
+            }
+            EntityKind::Decor {
+                image,
+                color: decor_color,
+                size,
+            } => {
+                parent.with_child((
+                    name,
+                    Sprite {
+                        image: image
+                            .as_ref()
+                            .map(|image| description.image(image))
+                            .unwrap_or_default(),
+                        color: color(*decor_color),
+                        custom_size: Some(Vec2::new(size.0, size.1)),
+                        ..default()
+                    },
+                    Transform::from_translation(translation),
+                ));
+            }
+        }
     }
 
-    for mut transform in &mut right_wall_query {
-        transform.translation.x = half_width - 50.0;
+    if !has_player {
+        warn!("{} has no player start, using the default one", level.level);
+        commands
+            .entity(level_entity)
+            .with_child(player(&player_assets, DEFAULT_PLAYER_START));
+    }
+    if let Some(music) = &current_level_assets.music {
+        commands
+            .entity(level_entity)
+            .with_child((Name::new("Level Music"), audio::music(music.clone())));
     }
 }
 
-/// System that updates background size when window is resized
-fn update_background_size(
-    mut background_query: Query<&mut Sprite, With<Background>>,
-    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+/// Keep the walls just outside the level, following window resizes for levels that are as
+/// wide as the screen.
+fn position_invisible_walls(
+    mut wall_query: Query<(&mut Transform, Has<LeftWall>), With<InvisibleWall>>,
+    new_walls: Query<(), Added<InvisibleWall>>,
+    bounds: Res<LevelBounds>,
+    window_query: Query<Ref<Window>, With<PrimaryWindow>>,
 ) {
-    // Only run when window has changed
-    for window in window_query.iter() {
-        let world_height = 600.0;
-        let window_aspect = window.width() / window.height();
-        let world_width = world_height * window_aspect;
+    let Ok(window) = window_query.single() else {
+        return; // Skip if window not ready yet
+    };
+    if !window.is_changed() && !bounds.is_changed() && new_walls.is_empty() {
+        return;
+    }
 
-        // Update only the background sprite
-        for mut sprite in background_query.iter_mut() {
-            sprite.custom_size = Some(Vec2::new(world_width, world_height));
-        }
+    let wall_x = bounds.half_width(&window) + WALL_THICKNESS / 2.0;
+    for (mut transform, is_left) in &mut wall_query {
+        transform.translation.x = if is_left { -wall_x } else { wall_x };
     }
 }
 
@@ -184,10 +328,9 @@ fn update_shop_sprite_size(
     window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
 ) {
     for window in window_query.iter() {
-        let world_height = 600.0;
         let window_aspect = window.width() / window.height();
         let base_resolution = 1920.0 / 1080.0;
-        let scale_factor = (window_aspect / base_resolution).min(1.2).max(0.8);
+        let scale_factor = (window_aspect / base_resolution).clamp(0.8, 1.2);
         let shop_size = 60.0 * scale_factor;
         for mut sprite in shop_query.iter_mut() {
             sprite.custom_size = Some(Vec2::new(shop_size, shop_size));
@@ -195,111 +338,81 @@ fn update_shop_sprite_size(
     }
 }
 
-fn left_wall() -> impl Bundle {
-    (
-        Name::new("Left Wall"),
-        InvisibleWall,
-        LeftWall,
-        Transform::default(),
-    )
-}
-
-fn right_wall() -> impl Bundle {
-    (
-        Name::new("Right Wall"),
-        InvisibleWall,
-        RightWall,
-        Transform::default(),
-    )
-}
-
-/// Creates the HDR background
-fn background(level_assets: &LevelAssets, window: &Window) -> impl Bundle {
-    // Calculate the world space dimensions that will be visible
-    // Camera uses FixedVertical scaling with 600px height
-    let world_height = 600.0;
-    let window_aspect = window.width() / window.height();
-    let world_width = world_height * window_aspect;
-
-    // Scale the background to fill the entire visible world space
-    (
-        Name::new("HDR Background"),
-        Background,
-        Sprite {
-            image: level_assets.background.clone(),
-            custom_size: Some(Vec2::new(world_width, world_height)),
-            ..default()
-        },
-        Transform::from_translation(Vec3::new(0.0, 0.0, -10.0)), // behind everything else
-    )
+/// Creates an HDR background layer. The camera resizes it to cover its scroll range.
+fn background(image: Handle<Image>, tint: Color, window: &Window) -> Sprite {
+    // Start out filling the visible world space
+    Sprite {
+        image,
+        color: tint,
+        custom_size: Some(Vec2::new(visible_width(window), 600.0)),
+        ..default()
+    }
 }
 
-/// Creates a coin box in the middle of the screen with shimmer effect
+/// Creates a coin box with shimmer effect
 fn coin_box(
     level_assets: &LevelAssets,
+    health: f32,
+    size: Vec2,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<CoinBoxMaterial>>,
 ) -> impl Bundle {
-    let coinbox_width = 70.0;
-    let coinbox_height = 70.0;
     (
-        Name::new("Coin Box"),
         Target::default(), // shootable with damage timers
-        Mesh2d(meshes.add(Rectangle::new(coinbox_width, coinbox_height))),
+        Health::new(health),
+        Mesh2d(meshes.add(Rectangle::from_size(size))),
         MeshMaterial2d(materials.add(CoinBoxMaterial {
             base_color_texture: level_assets.coin_box.clone(),
         })),
-        Transform::from_translation(Vec3::new(0.0, -130.0, 0.0)),
         RigidBody::Static,
-        Collider::rectangle(coinbox_width, coinbox_height),
+        Collider::rectangle(size.x, size.y),
         Sensor,
         CollisionEventsEnabled,
     )
 }
 
+fn invisible_wall() -> impl Bundle {
+    (
+        InvisibleWall,
+        Transform::default(),
+        RigidBody::Static,
+        Collider::rectangle(WALL_THICKNESS, WALL_HEIGHT),
+        Restitution::new(0.6), // Coins bounce back into the level
+        CollisionLayers::new(WALL_LAYER, LayerMask(0b0010)), // On layer 2, collides with layer 1 (coins)
+    )
+}
+
 /// Creates invisible ground for both player and coins
-fn invisible_ground(level_assets: &LevelAssets) -> impl Bundle {
+fn invisible_ground(width: f32) -> impl Bundle {
     (
-        Name::new("Invisible Ground"),
+        Ground,
         // No sprite - completely invisible
-        Transform::from_translation(Vec3::new(0.0, level_assets.ground_level, 0.0)),
         RigidBody::Static,
-        Collider::rectangle(2000.0, 20.0),
-        CollisionLayers::new(LayerMask(0b0001), LayerMask(0b0011)), // On layer 0, collides with layer 1 (coins) and layer 2 (player)
+        Collider::rectangle(width, 20.0),
+        CollisionLayers::new(GROUND_LAYER, LayerMask(0b0010)), // On layer 4, collides with layer 1 (coins)
     )
 }
 
-fn shop_box_upgrades(level_assets: &LevelAssets) -> impl Bundle {
+fn platform(width: f32, color: Color) -> impl Bundle {
     (
-        Name::new("Shop Box Upgrades"),
-        UpgradeShop,
-        ShopSprite,
-        Sprite {
-            image: level_assets.upgrade_shop.clone(),
-            custom_size: Some(Vec2::new(60.0, 60.0)),
-            ..default()
-        },
-        Transform::from_translation(Vec3::new(-190.0, level_assets.ground_level, 0.0)),
+        OneWayPlatform,
+        Sprite::from_color(color, Vec2::new(width, PLATFORM_THICKNESS)),
         RigidBody::Static,
-        Collider::rectangle(50.0, 50.0), // Shop interaction area
-        Sensor,
-        CollisionEventsEnabled,
+        Collider::rectangle(width, PLATFORM_THICKNESS),
+        CollisionLayers::new(PLATFORM_LAYER, LayerMask(0b0010)), // On layer 3, collides with layer 1 (coins)
     )
 }
 
-fn shop_box_weapons(level_assets: &LevelAssets) -> impl Bundle {
+fn shop_box(image: Handle<Image>) -> impl Bundle {
     (
-        Name::new("Shop Box Weapons"),
-        WeaponShop,
         ShopSprite,
         Sprite {
-            image: level_assets.weapon_shop.clone(),
+            image,
             custom_size: Some(Vec2::new(60.0, 60.0)),
             ..default()
         },
-        Transform::from_translation(Vec3::new(-300.0, level_assets.ground_level, 0.0)),
         RigidBody::Static,
-        Collider::rectangle(50.0, 50.0),
+        Collider::rectangle(50.0, 50.0), // Shop interaction area
         Sensor,
         CollisionEventsEnabled,
     )
@@ -0,0 +1,236 @@
+//! The `.level.ron` format describing what a level is made of.
+//!
+//! A level description is a list of entities with a position and a kind. The kinds cover
+//! everything a level can contain, and [`spawn_level`](super::level::spawn_level) turns them
+//! into entities, so new levels only need a new file.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<LevelDescription>();
+    app.init_asset_loader::<LevelDescriptionLoader>();
+}
+
+/// A level loaded from a `.level.ron` file.
+#[derive(Asset, TypePath, Debug)]
+pub struct LevelDescription {
+    /// Width of the level in world units. Levels without one are as wide as the screen.
+    pub width: Option<f32>,
+    pub entities: Vec<LevelEntity>,
+    /// Images referenced by the entities, keyed by their path.
+    pub images: HashMap<String, Handle<Image>>,
+}
+
+impl LevelDescription {
+    pub fn image(&self, path: &str) -> Handle<Image> {
+        self.images.get(path).cloned().unwrap_or_default()
+    }
+}
+
+/// The contents of a `.level.ron` file.
+#[derive(Debug, Deserialize, Serialize)]
+struct LevelFile {
+    #[serde(default)]
+    width: Option<f32>,
+    entities: Vec<LevelEntity>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LevelEntity {
+    /// Name shown in the inspector. Defaults to one based on the kind.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub position: (f32, f32),
+    /// Draw order. Backgrounds are drawn behind everything else, ordered among themselves by it.
+    #[serde(default)]
+    pub z: f32,
+    pub kind: EntityKind,
+}
+
+impl LevelEntity {
+    pub fn name(&self) -> Name {
+        Name::new(
+            self.name
+                .clone()
+                .unwrap_or_else(|| self.kind.default_name().to_string()),
+        )
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        Vec3::new(self.position.0, self.position.1, self.z)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum WallSide {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum EntityKind {
+    /// Image filling the screen. The tint lets levels share artwork.
+    Background {
+        image: String,
+        #[serde(default = "white")]
+        tint: (f32, f32, f32),
+        /// How much the layer lags behind the camera, from 0 (scrolls with the level) to 1
+        /// (stays put on screen).
+        #[serde(default = "one")]
+        parallax: f32,
+    },
+    /// Where the player starts.
+    PlayerStart,
+    /// A coin box that can be shot.
+    Target {
+        health: f32,
+        #[serde(default = "target_size")]
+        size: (f32, f32),
+    },
+    WeaponShop,
+    UpgradeShop,
+    /// Invisible floor for the player and coins.
+    Ground {
+        width: f32,
+    },
+    /// Ledge the player can jump onto from below, and coins can land on.
+    Platform {
+        width: f32,
+        #[serde(default = "platform_color")]
+        color: (f32, f32, f32),
+    },
+    /// Invisible wall at the edge of the screen. Its position follows the window.
+    Wall(WallSide),
+    /// Scenery without any behavior, either an image or a plain rectangle.
+    Decor {
+        #[serde(default)]
+        image: Option<String>,
+        #[serde(default = "white")]
+        color: (f32, f32, f32),
+        size: (f32, f32),
+    },
+}
+
+impl EntityKind {
+    fn default_name(&self) -> &'static str {
+        match self {
+            EntityKind::Background { .. } => "Background",
+            EntityKind::PlayerStart => "Player Start",
+            EntityKind::Target { .. } => "Coin Box",
+            EntityKind::WeaponShop => "Shop Box Weapons",
+            EntityKind::UpgradeShop => "Shop Box Upgrades",
+            EntityKind::Ground { .. } => "Ground",
+            EntityKind::Platform { .. } => "Platform",
+            EntityKind::Wall(WallSide::Left) => "Left Wall",
+            EntityKind::Wall(WallSide::Right) => "Right Wall",
+            EntityKind::Decor { .. } => "Decor",
+        }
+    }
+
+    fn image(&self) -> Option<&str> {
+        match self {
+            EntityKind::Background { image, .. } => Some(image),
+            EntityKind::Decor { image, .. } => image.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn white() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+fn platform_color() -> (f32, f32, f32) {
+    (0.45, 0.35, 0.25)
+}
+
+fn target_size() -> (f32, f32) {
+    (70.0, 70.0)
+}
+
+pub fn color((r, g, b): (f32, f32, f32)) -> Color {
+    Color::srgb(r, g, b)
+}
+
+#[derive(Default)]
+struct LevelDescriptionLoader;
+
+#[derive(Debug)]
+pub enum LevelDescriptionError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for LevelDescriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LevelDescriptionError::Io(e) => write!(f, "Could not read level file: {}", e),
+            LevelDescriptionError::Parse(e) => write!(f, "Could not parse level file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LevelDescriptionError {}
+
+impl From<std::io::Error> for LevelDescriptionError {
+    fn from(e: std::io::Error) -> Self {
+        LevelDescriptionError::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for LevelDescriptionError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        LevelDescriptionError::Parse(e)
+    }
+}
+
+impl AssetLoader for LevelDescriptionLoader {
+    type Asset = LevelDescription;
+    type Settings = ();
+    type Error = LevelDescriptionError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file = ron::de::from_bytes::<LevelFile>(&bytes)?;
+
+        // Images loaded through the context are dependencies of the level, so the loading
+        // screen waits for them as well.
+        let mut images = HashMap::new();
+        for path in file
+            .entities
+            .iter()
+            .filter_map(|entity| entity.kind.image())
+        {
+            images
+                .entry(path.to_string())
+                .or_insert_with(|| load_context.load(path.to_string()));
+        }
+
+        Ok(LevelDescription {
+            width: file.width,
+            entities: file.entities,
+            images,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
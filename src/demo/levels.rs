@@ -0,0 +1,293 @@
+//! The lands the player travels through.
+//!
+//! Levels are listed in `assets/levels/levels.ron`, each pointing at a `.level.ron` file with
+//! its contents. Once a level's travel goal is met, the player can walk off the right edge of
+//! the level to continue to the next one. After the last level the journey starts over from
+//! the first.
+
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetId},
+    prelude::*,
+    window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        achievements,
+        level::LevelBounds,
+        level_description::LevelDescription,
+        player::{Money, Player},
+        shop::shop::PlayerUpgrades,
+        stats::GameStats,
+    },
+    ron_asset::RonAssetApp,
+    screens::Screen,
+    toast::Toast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_ron_asset::<LevelsConfig>();
+    app.init_state::<CurrentLevel>();
+    app.init_resource::<TravelState>();
+
+    app.add_systems(Startup, load_level_registry);
+    app.add_systems(
+        Update,
+        insert_level_registry.run_if(resource_exists::<LevelsConfigHandle>),
+    );
+    app.add_systems(
+        Update,
+        load_current_level_assets.run_if(
+            resource_exists::<LevelRegistry>
+                .and(state_changed::<CurrentLevel>.or(resource_added::<LevelRegistry>)),
+        ),
+    );
+    app.add_systems(OnEnter(Screen::Gameplay), reset_travel_state);
+    app.add_systems(
+        Update,
+        (announce_open_road, travel_to_next_level)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const LEVELS_PATH: &str = "levels/levels.ron";
+/// How close to the right edge the player has to walk to leave the level.
+const EXIT_MARGIN: f32 = 24.0;
+
+/// Everything that makes up one land.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LevelData {
+    pub id: String,
+    pub name: String,
+    /// Path of the `.level.ron` file describing the land, relative to `assets/`.
+    pub level: String,
+    /// Path of the music played in this land, relative to `assets/`.
+    #[serde(default)]
+    pub music: Option<String>,
+    /// What has to be achieved before the player may travel on. `None` means the road is
+    /// always open.
+    #[serde(default)]
+    pub travel_goal: Option<achievements::Condition>,
+}
+
+impl Default for LevelData {
+    /// The original single level, used when the level registry can't be loaded.
+    fn default() -> Self {
+        Self {
+            id: "meadow".to_string(),
+            name: "The Meadow".to_string(),
+            level: "levels/meadow.level.ron".to_string(),
+            music: None,
+            travel_goal: None,
+        }
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Serialize)]
+pub struct LevelsConfig {
+    pub levels: Vec<LevelData>,
+}
+
+/// The level list while it is loading.
+#[derive(Resource)]
+struct LevelsConfigHandle(Handle<LevelsConfig>);
+
+/// All levels, in the order they are travelled through.
+#[derive(Resource)]
+pub struct LevelRegistry {
+    pub config: LevelsConfig,
+}
+
+impl Default for LevelRegistry {
+    fn default() -> Self {
+        Self {
+            config: LevelsConfig {
+                levels: vec![LevelData::default()],
+            },
+        }
+    }
+}
+
+impl LevelRegistry {
+    /// The level at `index`, wrapping around once the journey is complete.
+    pub fn get(&self, index: usize) -> &LevelData {
+        let levels = &self.config.levels;
+        &levels[index % levels.len()]
+    }
+
+    /// The level with `id`, if there is one.
+    pub fn find(&self, id: &str) -> Option<CurrentLevel> {
+        self.config
+            .levels
+            .iter()
+            .position(|level| level.id == id)
+            .map(CurrentLevel)
+    }
+
+    pub fn next(&self, level: CurrentLevel) -> CurrentLevel {
+        CurrentLevel((level.0 + 1) % self.config.levels.len())
+    }
+}
+
+/// Index of the level the player is in.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct CurrentLevel(pub usize);
+
+fn load_level_registry(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelsConfigHandle(asset_server.load(LEVELS_PATH)));
+}
+
+/// Turn the level list into the [`LevelRegistry`] once it finished loading.
+fn insert_level_registry(
+    mut commands: Commands,
+    handle: Res<LevelsConfigHandle>,
+    asset_server: Res<AssetServer>,
+    mut configs: ResMut<Assets<LevelsConfig>>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let registry = match asset_server.load_state(&handle.0) {
+        LoadState::Loaded => match configs.remove(&handle.0) {
+            Some(config) if !config.levels.is_empty() => {
+                info!("Loaded {} levels", config.levels.len());
+                LevelRegistry { config }
+            }
+            _ => {
+                error!("levels.ron doesn't contain any levels");
+                LevelRegistry::default()
+            }
+        },
+        LoadState::Failed(e) => {
+            error!("Failed to load levels.ron: {}", e);
+            toasts.write(Toast::error("Levels could not be loaded"));
+            LevelRegistry::default()
+        }
+        _ => return,
+    };
+    commands.insert_resource(registry);
+    commands.remove_resource::<LevelsConfigHandle>();
+}
+
+/// Handles to the assets of the current level, kept so they stay loaded.
+#[derive(Resource)]
+pub struct CurrentLevelAssets {
+    /// The level these assets belong to.
+    pub level: CurrentLevel,
+    pub description: Handle<LevelDescription>,
+    pub music: Option<Handle<AudioSource>>,
+}
+
+impl CurrentLevelAssets {
+    /// Whether loading finished for `level`. A level that failed to load counts as finished,
+    /// so the game doesn't get stuck on the loading screen.
+    pub fn is_loaded(&self, level: CurrentLevel, asset_server: &AssetServer) -> bool {
+        let done = |id: UntypedAssetId| {
+            matches!(
+                asset_server.get_recursive_dependency_load_state(id),
+                Some(
+                    RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_)
+                )
+            )
+        };
+        self.level == level
+            && done(self.description.id().untyped())
+            && self
+                .music
+                .as_ref()
+                .is_none_or(|music| done(music.id().untyped()))
+    }
+}
+
+fn load_current_level_assets(
+    mut commands: Commands,
+    registry: Res<LevelRegistry>,
+    level: Res<State<CurrentLevel>>,
+    asset_server: Res<AssetServer>,
+) {
+    let index = *level.get();
+    let level = registry.get(index.0);
+    commands.insert_resource(CurrentLevelAssets {
+        level: index,
+        description: asset_server.load(&level.level),
+        music: level.music.as_ref().map(|music| asset_server.load(music)),
+    });
+}
+
+/// Whether the assets of the current level finished loading.
+pub fn current_level_loaded(
+    asset_server: Res<AssetServer>,
+    level: Res<State<CurrentLevel>>,
+    level_assets: Option<Res<CurrentLevelAssets>>,
+) -> bool {
+    level_assets.is_some_and(|level_assets| level_assets.is_loaded(*level.get(), &asset_server))
+}
+
+/// Whether the player has been told the road to the next level is open.
+#[derive(Resource, Default)]
+struct TravelState {
+    road_open: bool,
+}
+
+fn reset_travel_state(mut travel: ResMut<TravelState>) {
+    travel.road_open = false;
+}
+
+fn announce_open_road(
+    registry: Res<LevelRegistry>,
+    level: Res<State<CurrentLevel>>,
+    stats: Res<GameStats>,
+    money: Res<Money>,
+    upgrades: Res<PlayerUpgrades>,
+    mut travel: ResMut<TravelState>,
+    mut toasts: EventWriter<Toast>,
+) {
+    if travel.road_open {
+        return;
+    }
+
+    let current = registry.get(level.0);
+    let goal_met = current
+        .travel_goal
+        .as_ref()
+        .is_none_or(|goal| goal.is_met(&stats, &money, &upgrades));
+    if goal_met {
+        travel.road_open = true;
+        let next = registry.get(registry.next(*level.get()).0);
+        toasts.write(
+            Toast::success(format!("The road to {} is open - head east", next.name))
+                .with_duration(5.0),
+        );
+    }
+}
+
+/// Leave the level once the player walks against the right wall with the road open.
+fn travel_to_next_level(
+    registry: Res<LevelRegistry>,
+    level: Res<State<CurrentLevel>>,
+    travel: Res<TravelState>,
+    bounds: Res<LevelBounds>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut next_level: ResMut<NextState<CurrentLevel>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if !travel.road_open {
+        return;
+    }
+    let (Ok(window), Ok(player)) = (window_query.single(), player_query.single()) else {
+        return;
+    };
+
+    if player.translation.x < bounds.half_width(window) - EXIT_MARGIN {
+        return;
+    }
+
+    let next = registry.next(*level.get());
+    info!("Travelling to {}", registry.get(next.0).name);
+    next_level.set(next);
+    next_screen.set(Screen::Loading);
+}
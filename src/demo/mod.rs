@@ -1,17 +1,38 @@
 use bevy::prelude::*;
 
+pub mod achievements;
 mod animation;
+mod camera;
+pub mod floating_text;
 mod hud;
 pub mod level;
+pub mod level_description;
+pub mod levels;
+pub mod modifiers;
 pub mod player;
+pub mod replay;
+pub mod rng;
 pub mod shop;
+pub mod stats;
+pub mod target;
+mod weapon_bar;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        achievements::plugin,
         animation::plugin,
+        camera::plugin,
+        floating_text::plugin,
         hud::plugin,
         level::plugin,
+        level_description::plugin,
+        levels::plugin,
+        modifiers::plugin,
         player::plugin,
         shop::plugin,
+        stats::plugin,
+        target::plugin,
+        weapon_bar::plugin,
     ));
+    app.add_plugins((replay::plugin, rng::plugin));
 }
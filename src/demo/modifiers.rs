@@ -0,0 +1,179 @@
+//! Player stats and the modifiers changing them.
+//!
+//! The [`Stats`] component holds a base value per [`Stat`] and a stack of modifiers. Upgrades
+//! and the equipped weapon add modifiers as configured in `items.ron`. Movement, shooting and
+//! economy code read the final values through [`Stats::get`] instead of doing their own math.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems,
+    demo::{
+        player::Player,
+        shop::shop::{ItemsData, MovementConfig, PlayerUpgrades, UpgradeType, WeaponType},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Stats>();
+
+    app.add_systems(
+        Update,
+        refresh_modifiers
+            .run_if(resource_changed::<PlayerUpgrades>.or(any_match_filter::<Added<Stats>>))
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum Stat {
+    /// Walking speed, in world units per second.
+    MoveSpeed,
+    /// How quickly walking speed is reached, in world units per second squared.
+    Acceleration,
+    /// How quickly the player stops or turns around, in world units per second squared.
+    Deceleration,
+    /// Multiplier of the damage dealt by weapons.
+    Damage,
+    /// Multiplier of the money coins are worth.
+    CoinValue,
+    /// Multiplier of how often weapons fire.
+    FireRate,
+    /// How many coins the buffer holds.
+    BufferCapacity,
+    /// Share of the buffer capacity drained per second.
+    BufferDrain,
+}
+
+impl Stat {
+    /// Value of the stat before any modifiers.
+    fn default_base(self) -> f32 {
+        match self {
+            Stat::MoveSpeed => MovementConfig::default().base_speed,
+            Stat::Acceleration => MovementConfig::default().acceleration,
+            Stat::Deceleration => MovementConfig::default().deceleration,
+            Stat::Damage | Stat::CoinValue | Stat::FireRate => 1.0,
+            Stat::BufferCapacity => 70.0,
+            Stat::BufferDrain => 0.1,
+        }
+    }
+}
+
+/// How a modifier changes a stat.
+///
+/// The final value is `(base + adds) * (1 + percents) * multipliers`, so percentages from
+/// several sources stack additively while multipliers compound.
+#[derive(Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
+pub enum ModifierKind {
+    Add(f32),
+    Percent(f32),
+    Multiply(f32),
+}
+
+/// A modifier as written in `items.ron`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ModifierData {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+}
+
+/// Where a modifier comes from, so it can be removed again.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub enum ModifierSource {
+    Upgrade(UpgradeType),
+    Equipment(WeaponType),
+}
+
+#[derive(Clone, Debug, Reflect)]
+pub struct Modifier {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+    pub source: ModifierSource,
+}
+
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Stats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<Modifier>,
+}
+
+impl Stats {
+    pub fn base(&self, stat: Stat) -> f32 {
+        self.base
+            .get(&stat)
+            .copied()
+            .unwrap_or_else(|| stat.default_base())
+    }
+
+    pub fn set_base(&mut self, stat: Stat, value: f32) {
+        self.base.insert(stat, value);
+    }
+
+    /// The value of a stat with all modifiers applied.
+    pub fn get(&self, stat: Stat) -> f32 {
+        let (mut add, mut percent, mut multiply) = (0.0, 0.0, 1.0);
+        for modifier in self
+            .modifiers
+            .iter()
+            .filter(|modifier| modifier.stat == stat)
+        {
+            match modifier.kind {
+                ModifierKind::Add(value) => add += value,
+                ModifierKind::Percent(value) => percent += value,
+                ModifierKind::Multiply(value) => multiply *= value,
+            }
+        }
+        (self.base(stat) + add) * (1.0 + percent) * multiply
+    }
+
+    pub fn add_modifier(&mut self, source: ModifierSource, modifier: ModifierData) {
+        self.modifiers.push(Modifier {
+            stat: modifier.stat,
+            kind: modifier.kind,
+            source,
+        });
+    }
+}
+
+/// Rebuild the modifiers from upgrades and equipment.
+fn refresh_modifiers(
+    items_data: Option<Res<ItemsData>>,
+    upgrades: Res<PlayerUpgrades>,
+    mut stats_query: Query<&mut Stats, With<Player>>,
+) {
+    let Some(items_data) = items_data else {
+        return;
+    };
+    let config = &items_data.config;
+
+    for mut stats in &mut stats_query {
+        stats.set_base(Stat::MoveSpeed, config.movement.base_speed);
+        stats.set_base(Stat::Acceleration, config.movement.acceleration);
+        stats.set_base(Stat::Deceleration, config.movement.deceleration);
+        stats.modifiers.clear();
+
+        // Upgrades stack one set of modifiers per level bought
+        for upgrade in config.upgrades.types.values() {
+            let upgrade_type = UpgradeType::from_string(&upgrade.upgrade_type);
+            for _ in 0..upgrades.level(upgrade_type) {
+                for modifier in upgrade.level_modifiers() {
+                    stats.add_modifier(ModifierSource::Upgrade(upgrade_type), modifier);
+                }
+            }
+        }
+
+        let equipped = config.weapon(upgrades.current_weapon);
+        for modifier in equipped.iter().flat_map(|weapon| &weapon.modifiers) {
+            stats.add_modifier(
+                ModifierSource::Equipment(upgrades.current_weapon),
+                *modifier,
+            );
+        }
+    }
+}
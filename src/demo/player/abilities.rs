@@ -0,0 +1,101 @@
+//! Movement abilities bought in the upgrade shop.
+//!
+//! Each ability is a component on the [`Player`], added once it's owned. The movement systems
+//! check for these components, so an ability does nothing until it's bought.
+
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::demo::{
+    player::{
+        Player,
+        movement::{CharacterController, Dash},
+    },
+    shop::shop::PlayerUpgrades,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DashAbility>();
+    app.register_type::<DoubleJumpAbility>();
+    app.register_type::<SprintAbility>();
+
+    app.add_observer(start_dash);
+    app.add_systems(
+        Update,
+        sync_abilities
+            .run_if(resource_changed::<PlayerUpgrades>.or(any_match_filter::<Added<Player>>)),
+    );
+}
+
+/// Seconds a dash lasts.
+const DASH_SECS: f32 = 0.15;
+/// Seconds between two dashes.
+const DASH_COOLDOWN_SECS: f32 = 1.0;
+
+/// A short burst of speed in the facing direction, ignoring gravity.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct DashAbility {
+    /// Seconds left on the current dash, zero when not dashing.
+    pub active_secs: f32,
+    /// Seconds until the next dash is possible.
+    pub cooldown_secs: f32,
+    pub direction: f32,
+}
+
+impl DashAbility {
+    pub fn is_dashing(&self) -> bool {
+        self.active_secs > 0.0
+    }
+}
+
+/// One extra jump in mid-air, recharged on landing.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct DoubleJumpAbility {
+    pub used: bool,
+}
+
+/// Faster walking while the sprint button is held.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct SprintAbility;
+
+/// Give the player the abilities they own.
+fn sync_abilities(
+    mut commands: Commands,
+    upgrades: Res<PlayerUpgrades>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    for player in &player_query {
+        let mut player = commands.entity(player);
+        if upgrades.dash {
+            player.insert_if_new(DashAbility::default());
+        } else {
+            player.remove::<DashAbility>();
+        }
+        if upgrades.double_jump {
+            player.insert_if_new(DoubleJumpAbility::default());
+        } else {
+            player.remove::<DoubleJumpAbility>();
+        }
+        if upgrades.sprint {
+            player.insert_if_new(SprintAbility);
+        } else {
+            player.remove::<SprintAbility>();
+        }
+    }
+}
+
+fn start_dash(
+    _: Trigger<Started<Dash>>,
+    mut player_query: Query<(&CharacterController, &mut DashAbility)>,
+) {
+    for (controller, mut dash) in &mut player_query {
+        if dash.cooldown_secs <= 0.0 {
+            dash.active_secs = DASH_SECS;
+            dash.cooldown_secs = DASH_COOLDOWN_SECS;
+            dash.direction = controller.facing;
+        }
+    }
+}
@@ -5,16 +5,17 @@ use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
-use crate::PausableSystems;
+use crate::{PausableSystems, demo::modifiers::Stats};
 
+pub mod abilities;
 pub mod movement;
 pub mod shooting;
 
-pub use movement::{DefaultInputContext, MovementSpeed, ScreenLimit};
+pub use movement::CharacterController;
 pub use shooting::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((movement::plugin, shooting::plugin));
+    app.add_plugins((abilities::plugin, movement::plugin, shooting::plugin));
     app.register_type::<Player>();
 
     app.register_type::<PlayerAssets>();
@@ -22,16 +23,12 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        (
-            update_player_size_on_window_resize,
-            add_input_context_to_player,
-        )
-            .in_set(PausableSystems),
+        update_player_size_on_window_resize.in_set(PausableSystems),
     );
 }
 
 /// The player character.
-pub fn player(max_speed: f32, player_assets: &PlayerAssets) -> impl Bundle {
+pub fn player(player_assets: &PlayerAssets, position: Vec2) -> impl Bundle {
     let player_height = 48.0;
     let player_width = 32.0;
 
@@ -43,18 +40,21 @@ pub fn player(max_speed: f32, player_assets: &PlayerAssets) -> impl Bundle {
             custom_size: Some(Vec2::new(player_width, player_height)),
             ..default()
         },
-        Transform::from_translation(Vec3::new(0.0, -240.0, 0.0)), // Same ground level as coins
-        MovementSpeed { max_speed },
-        ScreenLimit,
-        RigidBody::Kinematic,
-        LinearVelocity::ZERO,
-        GravityScale(0.0),
-        LockedAxes::ROTATION_LOCKED,
-        Collider::rectangle(player_width, player_height),
-        Sensor,
-        CollisionEventsEnabled,
-        LinearDamping(0.0),
-        AngularDamping(0.0),
+        Transform::from_translation(position.extend(0.0)),
+        Stats::default(),
+        CharacterController::default(),
+        AimDirection::default(),
+        (
+            RigidBody::Kinematic,
+            LinearVelocity::ZERO,
+            GravityScale(0.0),
+            LockedAxes::ROTATION_LOCKED,
+            Collider::rectangle(player_width, player_height),
+            Sensor,
+            CollisionEventsEnabled,
+            LinearDamping(0.0),
+            AngularDamping(0.0),
+        ),
     )
 }
 
@@ -76,15 +76,6 @@ fn update_player_size_on_window_resize(
     }
 }
 
-fn add_input_context_to_player(
-    mut commands: Commands,
-    player_query: Query<Entity, (With<Player>, Without<DefaultInputContext>)>,
-) {
-    for entity in &player_query {
-        commands.entity(entity).insert(DefaultInputContext);
-    }
-}
-
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct PlayerAssets {
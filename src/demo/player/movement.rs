@@ -3,92 +3,295 @@
 //!
 //! The movement system has the following logic:
 //! - Use bevy_enhanced_input actions to capture directional input (WASD/gamepad).
-//! - Apply movement based on input values and maximum speed with upgrade multipliers.
-//! - Constrain the player within screen boundaries.
+//! - Accelerate towards the walking speed given by the player's [`Stats`].
+//! - Apply gravity and jumps, with coyote time and jump buffering.
+//! - Apply the movement abilities the player owns: sprint, double jump and dash.
+//! - Stop the player at the level walls and land on the ground and one-way platforms.
 //!
-//! This implementation is designed for 2D horizontal movement in a side-scrolling game.
-//! The system supports both keyboard (A/D keys) and gamepad (left stick) input.
+//! This implementation is designed for a side-scrolling game.
+//! The system supports both keyboard (A/D keys) and gamepad (left stick) input, with the
+//! bindings chosen in the controls menu.
+//! The [`Fire`] and [`Aim`] actions bound here are used by the shooting module.
 
 use crate::demo::player::Player;
 use avian2d::prelude::*;
 use bevy::{platform::collections::HashSet, prelude::*};
-use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_enhanced_input::prelude::*;
 use std::any::TypeId;
 
-use crate::{AppSystems, PausableSystems, demo::shop::shop::PlayerUpgrades};
+use crate::{
+    AppSystems, PausableSystems,
+    controls::{ControlAction, ControlsConfig},
+    demo::{
+        level::{GROUND_LAYER, OneWayPlatform, PLATFORM_LAYER, WALL_LAYER},
+        modifiers::{Stat, Stats},
+        player::abilities::{DashAbility, DoubleJumpAbility, SprintAbility},
+    },
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_input_context::<DefaultInputContext>();
+    app.add_input_context::<UiInputContext>();
     app.add_observer(bind_default_inputs);
+    app.add_observer(bind_ui_inputs);
+    app.add_observer(buffer_jump);
+    app.register_type::<CharacterController>();
 
     app.init_resource::<BlocksInput>();
     app.register_type::<BlocksInput>();
     app.add_systems(
         PreUpdate,
-        update_player_input_binding.run_if(resource_changed::<BlocksInput>),
+        (
+            update_player_input_binding
+                .run_if(resource_changed::<BlocksInput>.or(any_match_filter::<Added<Player>>)),
+            apply_rebinds.run_if(resource_changed::<ControlsConfig>),
+        ),
     );
 
     app.add_systems(
         Update,
-        (apply_enhanced_movement, apply_screen_limits)
+        (
+            apply_enhanced_movement,
+            apply_jump_and_gravity,
+            apply_dash,
+            move_player,
+        )
             .chain()
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
 }
 
+/// Downward acceleration of the player, in world units per second squared.
+const GRAVITY: f32 = 1800.0;
+const MAX_FALL_SPEED: f32 = 1200.0;
+const JUMP_SPEED: f32 = 700.0;
+/// How long after walking off a ledge the player can still jump.
+const COYOTE_SECS: f32 = 0.1;
+/// How long a jump pressed just before landing is remembered.
+const JUMP_BUFFER_SECS: f32 = 0.12;
+/// Speed multiplier while sprinting.
+const SPRINT_MULTIPLIER: f32 = 1.5;
+const DASH_SPEED: f32 = 1400.0;
+/// Gap kept between the player and whatever they stand on or walk against.
+const SKIN: f32 = 0.5;
+
+/// Velocity and jump state of the player. The player is moved by shape casts rather than the
+/// physics solver, so it stops against level geometry and stays put while paused.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-pub struct MovementSpeed {
-    pub max_speed: f32,
+pub struct CharacterController {
+    pub velocity: Vec2,
+    pub grounded: bool,
+    /// Seconds since the player last stood on something.
+    pub airborne_secs: f32,
+    /// Seconds left on a buffered jump press.
+    pub jump_buffer: Option<f32>,
+    /// Whether the player left the ground by jumping, which rules out coyote jumps.
+    pub jumped: bool,
+    /// 1 when facing right, -1 when facing left.
+    pub facing: f32,
 }
 
-impl Default for MovementSpeed {
+impl Default for CharacterController {
     fn default() -> Self {
-        Self { max_speed: 400.0 }
+        Self {
+            velocity: Vec2::ZERO,
+            grounded: false,
+            airborne_secs: 0.0,
+            jump_buffer: None,
+            jumped: false,
+            facing: 1.0,
+        }
+    }
+}
+
+impl CharacterController {
+    fn can_jump(&self) -> bool {
+        !self.jumped && (self.grounded || self.airborne_secs <= COYOTE_SECS)
     }
 }
 
 fn apply_enhanced_movement(
-    upgrades: Res<PlayerUpgrades>,
-    mut movement_query: Query<(&MovementSpeed, &mut LinearVelocity), With<Player>>,
-    move_action: Single<&Action<Move>>,
+    time: Res<Time>,
+    mut movement_query: Query<(&Stats, &mut CharacterController, Has<SprintAbility>), With<Player>>,
+    move_action: Option<Single<&Action<Move>>>,
+    sprint_action: Option<Single<&Action<Sprint>>>,
 ) {
-    for (movement_speed, mut velocity) in &mut movement_query {
-        let move_input = **move_action;
-        let speed_multiplier = 1.0 + (upgrades.speed_boost as f32 * 0.3);
+    // Without the actions input is blocked, so the player comes to a stop
+    let move_input = move_action.map_or(Vec3::ZERO, |action| ***action);
+    let sprinting = sprint_action.is_some_and(|sprint| ***sprint);
+    for (stats, mut controller, can_sprint) in &mut movement_query {
+        let mut target_speed = stats.get(Stat::MoveSpeed) * move_input.x;
+        if can_sprint && sprinting {
+            target_speed *= SPRINT_MULTIPLIER;
+        }
 
-        velocity.x = movement_speed.max_speed * speed_multiplier * move_input.x;
-        velocity.y = 0.0;
+        // Speed up towards the target, but slow down when stopping or turning around
+        let velocity = controller.velocity.x;
+        let rate = if target_speed == 0.0 || target_speed * velocity < 0.0 {
+            stats.get(Stat::Deceleration)
+        } else {
+            stats.get(Stat::Acceleration)
+        };
+        let max_change = rate * time.delta_secs();
+        controller.velocity.x = velocity + (target_speed - velocity).clamp(-max_change, max_change);
+        if move_input.x != 0.0 {
+            controller.facing = move_input.x.signum();
+        }
     }
 }
 
-#[derive(Component, Reflect)]
-#[reflect(Component)]
-pub struct ScreenLimit;
+fn buffer_jump(_: Trigger<Started<Jump>>, mut controller_query: Query<&mut CharacterController>) {
+    for mut controller in &mut controller_query {
+        controller.jump_buffer = Some(JUMP_BUFFER_SECS);
+    }
+}
 
-fn apply_screen_limits(
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    mut player_query: Query<&mut Transform, With<ScreenLimit>>,
+fn apply_jump_and_gravity(
+    time: Res<Time>,
+    mut controller_query: Query<(&mut CharacterController, Option<&mut DoubleJumpAbility>)>,
 ) {
-    if let Ok(window) = window_query.single() {
-        let window_aspect = window.width() / window.height();
-        let viewport_height = 600.0; // Fixed height from camera setup
-        let viewport_width = viewport_height * window_aspect;
-        let half_width = viewport_width / 2.0;
-
-        let left_boundary = -half_width + 16.0;
-        let right_boundary = half_width - 16.0;
-
-        for mut player_transform in &mut player_query {
-            if player_transform.translation.x < left_boundary {
-                player_transform.translation.x = left_boundary;
+    let delta = time.delta_secs();
+    for (mut controller, mut double_jump) in &mut controller_query {
+        if controller.grounded {
+            controller.airborne_secs = 0.0;
+            controller.jumped = false;
+            if let Some(double_jump) = &mut double_jump {
+                double_jump.used = false;
             }
-            if player_transform.translation.x > right_boundary {
-                player_transform.translation.x = right_boundary;
+        } else {
+            controller.airborne_secs += delta;
+        }
+
+        if controller.jump_buffer.is_some() {
+            let can_jump = if controller.can_jump() {
+                true
+            } else if let Some(double_jump) = double_jump.as_mut().filter(|jump| !jump.used) {
+                double_jump.used = true;
+                true
+            } else {
+                false
+            };
+            if can_jump {
+                controller.jump_buffer = None;
+                controller.jumped = true;
+                controller.grounded = false;
+                controller.velocity.y = JUMP_SPEED;
             }
         }
+        controller.jump_buffer = controller
+            .jump_buffer
+            .map(|remaining| remaining - delta)
+            .filter(|remaining| *remaining > 0.0);
+
+        controller.velocity.y = (controller.velocity.y - GRAVITY * delta).max(-MAX_FALL_SPEED);
+    }
+}
+
+/// Override walking and falling while a dash is underway.
+fn apply_dash(
+    time: Res<Time>,
+    mut dash_query: Query<(&mut CharacterController, &mut DashAbility)>,
+) {
+    let delta = time.delta_secs();
+    for (mut controller, mut dash) in &mut dash_query {
+        dash.cooldown_secs = (dash.cooldown_secs - delta).max(0.0);
+        if dash.is_dashing() {
+            dash.active_secs = (dash.active_secs - delta).max(0.0);
+            controller.velocity = Vec2::new(DASH_SPEED * dash.direction, 0.0);
+        }
+    }
+}
+
+/// Move the player, stopping at walls and landing on the ground and platforms.
+///
+/// Platforms are one-way: they are only landed on when the player's feet start out above them.
+fn move_player(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    platform_query: Query<(&GlobalTransform, &Collider), With<OneWayPlatform>>,
+    mut player_query: Query<
+        (Entity, &mut Transform, &Collider, &mut CharacterController),
+        With<Player>,
+    >,
+) {
+    let delta = time.delta_secs();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (entity, mut transform, collider, mut controller) in &mut player_query {
+        let mut position = transform.translation.truncate();
+        let filter =
+            |mask: LayerMask| SpatialQueryFilter::from_mask(mask).with_excluded_entities([entity]);
+        let config = |distance: f32| ShapeCastConfig {
+            max_distance: distance,
+            target_distance: SKIN,
+            // Moving out of something the player overlaps is fine, moving further in isn't
+            ignore_origin_penetration: true,
+            ..default()
+        };
+
+        let step_x = controller.velocity.x * delta;
+        if let Ok(direction) = Dir2::new(Vec2::new(step_x, 0.0)) {
+            position.x += match spatial_query.cast_shape(
+                collider,
+                position,
+                0.0,
+                direction,
+                &config(step_x.abs()),
+                &filter(WALL_LAYER),
+            ) {
+                Some(hit) => {
+                    controller.velocity.x = 0.0;
+                    direction.x * hit.distance
+                }
+                None => step_x,
+            };
+        }
+
+        let step_y = controller.velocity.y * delta;
+        controller.grounded = false;
+        if step_y < 0.0 {
+            let feet = position.y - collider.aabb(position, Rotation::default()).size().y / 2.0;
+            let landing = spatial_query
+                .shape_hits(
+                    collider,
+                    position,
+                    0.0,
+                    Dir2::NEG_Y,
+                    4,
+                    &config(-step_y),
+                    &filter(GROUND_LAYER | PLATFORM_LAYER),
+                )
+                .into_iter()
+                .filter(|hit| match platform_query.get(hit.entity) {
+                    // Platforms can be jumped through from below
+                    Ok((platform, shape)) => {
+                        let top = shape
+                            .aabb(platform.translation().truncate(), Rotation::default())
+                            .max
+                            .y;
+                        feet >= top - SKIN
+                    }
+                    Err(_) => true,
+                })
+                .map(|hit| hit.distance)
+                .reduce(f32::min);
+            match landing {
+                Some(distance) => {
+                    position.y -= distance;
+                    controller.velocity.y = 0.0;
+                    controller.grounded = true;
+                }
+                None => position.y += step_y,
+            }
+        } else {
+            position.y += step_y;
+        }
+
+        transform.translation = position.extend(transform.translation.z);
     }
 }
 
@@ -103,29 +306,75 @@ pub(crate) struct Jump;
 
 #[derive(Debug, InputAction)]
 #[action_output(bool)]
-pub(crate) struct Interact;
+pub(crate) struct Dash;
 
 #[derive(Debug, InputAction)]
-#[action_output(Vec2)]
-pub(crate) struct Rotate;
+#[action_output(bool)]
+pub(crate) struct Sprint;
 
 #[derive(Debug, InputAction)]
 #[action_output(bool)]
-pub(crate) struct PickupProp;
+pub(crate) struct Interact;
 
 #[derive(Debug, InputAction)]
 #[action_output(bool)]
-pub(crate) struct DropProp;
+pub(crate) struct Fire;
+
+/// Aim direction from the right stick. Mouse aiming follows the cursor instead.
+#[derive(Debug, InputAction)]
+#[action_output(Vec2)]
+pub(crate) struct Aim;
+
+/// Closes the open shop, or goes back one menu.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct Back;
 
 #[derive(Debug, Component, Default)]
 pub struct DefaultInputContext;
 
+/// Replaces [`DefaultInputContext`] on the player while something blocks input.
+#[derive(Debug, Component, Default)]
+pub struct UiInputContext;
+
+/// Types of the open UI that keep the player from moving and shooting.
 #[derive(Resource, Default, Reflect, Deref, DerefMut)]
 #[reflect(Resource)]
 pub(crate) struct BlocksInput(HashSet<TypeId>);
 
-fn bind_default_inputs(trigger: Trigger<OnAdd, DefaultInputContext>, mut commands: Commands) {
-    const DEFAULT_SENSITIVITY: f32 = 0.002;
+/// Run condition that is true while nothing blocks gameplay input.
+pub(crate) fn input_unblocked(blocks_input: Res<BlocksInput>) -> bool {
+    blocks_input.is_empty()
+}
+
+/// Block gameplay input while any entity with `T` exists.
+pub(crate) fn block_input_while_spawned<T: Component>(app: &mut App) {
+    app.add_observer(
+        |_: Trigger<OnAdd, T>, mut blocks_input: ResMut<BlocksInput>| {
+            blocks_input.insert(TypeId::of::<T>());
+        },
+    );
+    app.add_observer(
+        |_: Trigger<OnRemove, T>,
+         query: Query<(), With<T>>,
+         mut blocks_input: ResMut<BlocksInput>| {
+            // The entity being removed still counts
+            if query.iter().count() <= 1 {
+                blocks_input.remove(&TypeId::of::<T>());
+            }
+        },
+    );
+}
+
+fn bind_default_inputs(
+    trigger: Trigger<OnAdd, DefaultInputContext>,
+    controls: Res<ControlsConfig>,
+    mut commands: Commands,
+) {
+    let [left_key, left_alt_key, left_button] =
+        controls.get(ControlAction::MoveLeft).to_enhanced_input();
+    let [right_key, right_alt_key, right_button] =
+        controls.get(ControlAction::MoveRight).to_enhanced_input();
     commands
         .entity(trigger.target())
         .insert(actions!(DefaultInputContext[
@@ -134,30 +383,70 @@ fn bind_default_inputs(trigger: Trigger<OnAdd, DefaultInputContext>, mut command
                 DeadZone::default(),
                 SmoothNudge::default(),
                 Bindings::spawn((
-                    Cardinal::wasd_keys(),
+                    Bidirectional { positive: right_key, negative: left_key },
+                    Bidirectional { positive: right_alt_key, negative: left_alt_key },
+                    Bidirectional { positive: right_button, negative: left_button },
                     Axial::left_stick()
                 ))
             ),
-            (Action::<Jump>::new(), bindings![KeyCode::Space, GamepadButton::South]),
-            (Action::<Interact>::new(), bindings![KeyCode::KeyE, GamepadButton::South]),
-            (Action::<Rotate>::new(),Negate::all(), Scale::splat(DEFAULT_SENSITIVITY),
-                Bindings::spawn((Spawn(Binding::mouse_motion()), Axial::right_stick()))),
-            (Action::<PickupProp>::new(), bindings![MouseButton::Left, GamepadButton::East]),
-            (Action::<DropProp>::new(), bindings![MouseButton::Right, GamepadButton::East]),
+            (Action::<Jump>::new(), bound(&controls, ControlAction::Jump)),
+            (Action::<Dash>::new(), bound(&controls, ControlAction::Dash)),
+            (Action::<Sprint>::new(), bound(&controls, ControlAction::Sprint)),
+            (Action::<Interact>::new(), bound(&controls, ControlAction::Interact)),
+            (Action::<Fire>::new(), bound(&controls, ControlAction::Fire)),
+            (Action::<Aim>::new(), DeadZone::default(), Bindings::spawn(Axial::right_stick())),
+        ]));
+}
+
+/// The bindings of `action` chosen in the controls menu.
+fn bound(controls: &ControlsConfig, action: ControlAction) -> impl Bundle {
+    let [keyboard, alt_keyboard, gamepad] = controls.get(action).to_enhanced_input();
+    Bindings::spawn((Spawn(keyboard), Spawn(alt_keyboard), Spawn(gamepad)))
+}
+
+/// Rebuild the player's actions with the new bindings.
+fn apply_rebinds(
+    player: Single<Entity, (With<Player>, With<DefaultInputContext>)>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(*player)
+        .remove_with_requires::<DefaultInputContext>()
+        .despawn_related::<Actions<DefaultInputContext>>()
+        .insert(DefaultInputContext);
+}
+
+/// Run condition that is true on the frame [`Back`] is pressed.
+pub(crate) fn back_just_pressed(back_query: Query<&ActionEvents, With<Action<Back>>>) -> bool {
+    back_query
+        .iter()
+        .any(|events| events.contains(ActionEvents::STARTED))
+}
+
+fn bind_ui_inputs(trigger: Trigger<OnAdd, UiInputContext>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .insert(actions!(UiInputContext[
+            (Action::<Back>::new(), bindings![GamepadButton::East]),
         ]));
 }
 
+/// Switch the player between gameplay and UI input.
 fn update_player_input_binding(
     player: Single<Entity, With<Player>>,
     blocks_input: Res<BlocksInput>,
     mut commands: Commands,
 ) {
+    let mut player = commands.entity(*player);
     if blocks_input.is_empty() {
-        commands.entity(*player).insert(DefaultInputContext);
+        player
+            .remove_with_requires::<UiInputContext>()
+            .despawn_related::<Actions<UiInputContext>>()
+            .insert(DefaultInputContext);
     } else {
-        commands
-            .entity(*player)
+        player
             .remove_with_requires::<DefaultInputContext>()
-            .despawn_related::<Actions<DefaultInputContext>>();
+            .despawn_related::<Actions<DefaultInputContext>>()
+            .insert(UiInputContext);
     }
 }
@@ -1,25 +1,36 @@
 //! Shooting system for player projectiles
 
 use avian2d::prelude::*;
-use bevy::input::mouse::MouseButton;
-use bevy::window::PrimaryWindow;
+use bevy::window::{CursorMoved, PrimaryWindow};
 use bevy::{
     prelude::*,
     reflect::TypePath,
     render::render_resource::{AsBindGroup, ShaderRef},
     sprite::{Material2d, Material2dPlugin},
 };
+use bevy_enhanced_input::prelude::*;
 use rand::Rng;
 
 use crate::{
     AppSystems, PausableSystems,
-    demo::hud::{CoinBuffer, ScoreText},
-    demo::level::LevelAssets,
-    demo::shop::shop::{PlayerUpgrades, WeaponType},
+    demo::hud::CoinBuffer,
+    demo::level::{Ground, LevelAssets, OneWayPlatform},
+    demo::modifiers::{Stat, Stats},
+    demo::replay::is_replaying,
+    demo::rng::{GameRng, RngStream},
+    demo::shop::shop::{ItemsData, PlayerUpgrades, WeaponType},
+    demo::stats::{
+        CoinCollected, CoinPickedUp, CoinsSpawned, ProjectileMissed, ShotFired, TargetGrazed,
+        TargetHit,
+    },
+    demo::target::Health,
     screens::Screen,
 };
 
-use super::{Player, ScreenLimit};
+use super::{
+    CharacterController, Player,
+    movement::{Aim, Fire},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Projectile>();
@@ -29,18 +40,32 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<LaserBeam>();
     app.register_type::<Money>();
     app.init_resource::<Money>();
+    app.register_type::<WeaponCooldown>();
+    app.init_resource::<WeaponCooldown>();
+    app.register_type::<AimDirection>();
 
     app.add_plugins(Material2dPlugin::<CoinMaterial>::default());
 
+    app.add_systems(
+        Update,
+        tick_weapon_cooldown
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
     app.add_systems(
         Update,
         (
-            handle_shooting,
-            handle_laser_beam,
+            (
+                update_aim_direction.run_if(not(is_replaying)),
+                handle_shooting,
+            )
+                .chain(),
+            expire_projectiles,
+            handle_laser_beam.after(update_aim_direction),
             handle_projectile_collisions,
             handle_laser_continuous_damage,
             collect_coins,
-            update_money_display,
             mark_coins_for_landing,
             disable_marked_coin_physics,
         )
@@ -74,6 +99,10 @@ impl Projectile {
     }
 }
 
+/// Marks a projectile that passed through a target on its damage cooldown.
+#[derive(Component)]
+struct GrazedTarget;
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Coin {
@@ -97,6 +126,7 @@ pub struct LaserBeam {
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
+#[require(Health)]
 pub struct Target {
     pub last_damage_time: f32,
     pub laser_damage_timer: f32,
@@ -117,37 +147,159 @@ pub struct Money {
     pub amount: u32,
 }
 
-fn handle_shooting(
-    mouse_input: Res<ButtonInput<MouseButton>>,
+/// Time until the current weapon can fire again.
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct WeaponCooldown {
+    pub remaining: f32,
+    pub duration: f32,
+}
+
+impl WeaponCooldown {
+    pub fn is_ready(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    pub fn start(&mut self, duration: f32) {
+        self.remaining = duration;
+        self.duration = duration;
+    }
+
+    /// Share of the cooldown still left, between 0 and 1.
+    pub fn fraction_remaining(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.remaining / self.duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+fn tick_weapon_cooldown(time: Res<Time>, mut cooldown: ResMut<WeaponCooldown>) {
+    if !cooldown.is_ready() {
+        cooldown.remaining -= time.delta_secs();
+    }
+}
+
+/// Greatest angle between the stick and a target for aim assist to kick in, in radians.
+const AIM_ASSIST_ANGLE: f32 = 0.3;
+/// Targets further away than this get no aim assist.
+const AIM_ASSIST_RANGE: f32 = 900.0;
+
+/// Where the player is aiming, from the mouse cursor or the right stick.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct AimDirection {
+    pub direction: Vec2,
+    /// Whether the stick was used last. The stick aim is kept after letting go, until the
+    /// mouse moves.
+    pub gamepad: bool,
+}
+
+impl Default for AimDirection {
+    fn default() -> Self {
+        Self {
+            direction: Vec2::X,
+            gamepad: false,
+        }
+    }
+}
+
+fn update_aim_direction(
+    mut cursor_moved: EventReader<CursorMoved>,
+    aim_action: Option<Single<&Action<Aim>>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    player_query: Query<&Transform, With<Player>>,
+    target_query: Query<&Transform, (With<Target>, Without<Player>)>,
+    mut player_query: Query<(&Transform, &CharacterController, &mut AimDirection), With<Player>>,
+) {
+    let Ok((player_transform, controller, mut aim)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    if cursor_moved.read().count() > 0 {
+        aim.gamepad = false;
+    }
+
+    let stick = aim_action.map_or(Vec2::ZERO, |action| ***action);
+    if stick != Vec2::ZERO {
+        aim.gamepad = true;
+        aim.direction = assist_aim(player_pos, stick.normalize(), &target_query);
+        return;
+    }
+    if aim.gamepad {
+        return;
+    }
+
+    let cursor_world_pos = match (camera_query.single(), window_query.single()) {
+        (Ok((camera, camera_transform)), Ok(window)) => {
+            window.cursor_position().and_then(|cursor_pos| {
+                camera
+                    .viewport_to_world_2d(camera_transform, cursor_pos)
+                    .ok()
+            })
+        }
+        _ => None,
+    };
+    aim.direction = match cursor_world_pos {
+        Some(world_pos) => (world_pos - player_pos).normalize_or(aim.direction),
+        // Without a cursor in the window, shoot the way the player faces
+        None => Vec2::new(controller.facing, 0.0),
+    };
+}
+
+/// Bend stick aim toward the nearest target close to where the stick points.
+fn assist_aim(
+    player_pos: Vec2,
+    direction: Vec2,
+    target_query: &Query<&Transform, (With<Target>, Without<Player>)>,
+) -> Vec2 {
+    target_query
+        .iter()
+        .map(|transform| transform.translation.truncate() - player_pos)
+        .filter(|offset| {
+            offset.length() <= AIM_ASSIST_RANGE
+                && direction.angle_to(*offset).abs() <= AIM_ASSIST_ANGLE
+        })
+        .min_by(|a, b| a.length().total_cmp(&b.length()))
+        .and_then(|offset| offset.try_normalize())
+        .unwrap_or(direction)
+}
+
+fn handle_shooting(
+    fire_action: Option<Single<(&ActionState, &ActionEvents), With<Action<Fire>>>>,
+    player_query: Query<(&Transform, &Stats, &AimDirection), With<Player>>,
     upgrades: Res<PlayerUpgrades>,
+    items_data: Option<Res<ItemsData>>,
     mut commands: Commands,
     existing_laser_query: Query<Entity, With<LaserBeam>>,
+    mut cooldown: ResMut<WeaponCooldown>,
+    mut shots: EventWriter<ShotFired>,
 ) {
+    // The fire action is gone while menus block input
+    let (firing, fire_started) = fire_action.map_or((false, false), |action| {
+        let (state, events) = *action;
+        (
+            *state == ActionState::Fired,
+            events.contains(ActionEvents::STARTED),
+        )
+    });
+
     if upgrades.current_weapon == WeaponType::LaserBeam {
-        if mouse_input.pressed(MouseButton::Right) {
+        if firing {
             // Keep laser active, spawn if doesn't exist
-            if existing_laser_query.is_empty() {
-                if let (Ok((camera, camera_transform)), Ok(window), Ok(player_transform)) = (
-                    camera_query.single(),
-                    window_query.single(),
-                    player_query.single(),
-                ) {
-                    if let Some(cursor_pos) = window.cursor_position() {
-                        if let Ok(world_pos) =
-                            camera.viewport_to_world_2d(camera_transform, cursor_pos)
-                        {
-                            let player_pos = player_transform.translation.truncate();
-                            let direction = (world_pos - player_pos).normalize();
-                            spawn_continuous_laser(&mut commands, player_pos, direction);
-                        }
-                    }
-                }
+            if existing_laser_query.is_empty()
+                && let Ok((player_transform, _, aim)) = player_query.single()
+            {
+                let player_pos = player_transform.translation.truncate();
+                spawn_continuous_laser(&mut commands, player_pos, aim.direction);
+                shots.write(ShotFired {
+                    weapon: WeaponType::LaserBeam,
+                });
             }
         } else {
-            // Mouse released, remove laser
+            // Fire released, remove laser
             for laser_entity in &existing_laser_query {
                 commands.entity(laser_entity).despawn();
             }
@@ -155,101 +307,83 @@ fn handle_shooting(
         return;
     }
 
-    if mouse_input.just_pressed(MouseButton::Right) {
-        if let (Ok((camera, camera_transform)), Ok(window), Ok(player_transform)) = (
-            camera_query.single(),
-            window_query.single(),
-            player_query.single(),
-        ) {
-            if let Some(cursor_pos) = window.cursor_position() {
-                if let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) {
-                    let player_pos = player_transform.translation.truncate();
-                    let direction = (world_pos - player_pos).normalize();
-
-                    match upgrades.current_weapon {
-                        WeaponType::Normal => {
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::Normal,
-                            );
-                        }
-                        WeaponType::RapidFire => {
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::RapidFire,
-                            );
-                        }
-                        WeaponType::SpreadShot => {
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::SpreadShot,
-                            );
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction.rotate(Vec2::from_angle(0.2)),
-                                WeaponType::SpreadShot,
-                            );
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction.rotate(Vec2::from_angle(-0.2)),
-                                WeaponType::SpreadShot,
-                            );
-                        }
-                        WeaponType::LaserBeam => {
-                            //FIXME still buggy
-                            spawn_laser_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::LaserBeam,
-                            );
-                        }
-                        WeaponType::Uzi => {
-                            spawn_projectile(&mut commands, player_pos, direction, WeaponType::Uzi);
-                        }
-                        WeaponType::Sniper => {
-                            spawn_sniper_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::Sniper,
-                            );
-                        }
-                        WeaponType::Bazooka => {
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::Bazooka,
-                            );
-                        }
-                        WeaponType::Hammer => {
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::Hammer,
-                            );
-                        }
-                        WeaponType::Sword => {
-                            spawn_projectile(
-                                &mut commands,
-                                player_pos,
-                                direction,
-                                WeaponType::Sword,
-                            );
-                        }
-                    }
-                }
+    if fire_started
+        && cooldown.is_ready()
+        && let Ok((player_transform, player_stats, aim)) = player_query.single()
+    {
+        let player_pos = player_transform.translation.truncate();
+        let direction = aim.direction;
+
+        shots.write(ShotFired {
+            weapon: upgrades.current_weapon,
+        });
+        let cooldown_secs = items_data
+            .as_ref()
+            .and_then(|items_data| items_data.config.weapon(upgrades.current_weapon))
+            .map_or(0.0, |weapon| weapon.cooldown_secs);
+        cooldown.start(cooldown_secs / player_stats.get(Stat::FireRate));
+        match upgrades.current_weapon {
+            WeaponType::Normal => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::Normal);
+            }
+            WeaponType::RapidFire => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::RapidFire);
+            }
+            WeaponType::SpreadShot => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::SpreadShot);
+                spawn_projectile(
+                    &mut commands,
+                    player_pos,
+                    direction.rotate(Vec2::from_angle(0.2)),
+                    WeaponType::SpreadShot,
+                );
+                spawn_projectile(
+                    &mut commands,
+                    player_pos,
+                    direction.rotate(Vec2::from_angle(-0.2)),
+                    WeaponType::SpreadShot,
+                );
+            }
+            WeaponType::LaserBeam => {
+                //FIXME still buggy
+                spawn_laser_projectile(&mut commands, player_pos, direction, WeaponType::LaserBeam);
+            }
+            WeaponType::Uzi => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::Uzi);
+            }
+            WeaponType::Sniper => {
+                spawn_sniper_projectile(&mut commands, player_pos, direction, WeaponType::Sniper);
+            }
+            WeaponType::Bazooka => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::Bazooka);
+            }
+            WeaponType::Hammer => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::Hammer);
+            }
+            WeaponType::Sword => {
+                spawn_projectile(&mut commands, player_pos, direction, WeaponType::Sword);
+            }
+        }
+    }
+}
+
+/// Despawn projectiles that flew for their whole lifetime without damaging anything
+fn expire_projectiles(
+    time: Res<Time>,
+    mut projectile_query: Query<(Entity, &mut Projectile, Has<GrazedTarget>)>,
+    mut misses: EventWriter<ProjectileMissed>,
+    mut grazes: EventWriter<TargetGrazed>,
+    mut commands: Commands,
+) {
+    for (entity, mut projectile, grazed) in &mut projectile_query {
+        projectile.lifetime -= time.delta_secs();
+        if projectile.lifetime <= 0.0 {
+            if grazed {
+                grazes.write(TargetGrazed);
+            } else {
+                misses.write(ProjectileMissed);
             }
+            commands.entity(entity).despawn();
         }
     }
 }
@@ -347,12 +481,16 @@ fn handle_projectile_collisions(
     mut collision_events: EventReader<CollisionStarted>,
     projectile_query: Query<&Projectile>,
     mut target_query: Query<(&Transform, &mut Target)>,
+    player_stats: Single<&Stats, With<Player>>,
     mut commands: Commands,
     level_assets: Res<LevelAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut coin_materials: ResMut<Assets<CoinMaterial>>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut hits: EventWriter<TargetHit>,
+    mut coins_spawned: EventWriter<CoinsSpawned>,
 ) {
     for CollisionStarted(entity1, entity2) in collision_events.read() {
         // Check if one entity is a projectile and the other is a target
@@ -372,27 +510,53 @@ fn handle_projectile_collisions(
             let current_time = time.elapsed_secs();
 
             // Prevent rapid-fire damage from same weapon type
-            if current_time - target.last_damage_time > 0.1 {
+            if current_time - target.last_damage_time <= 0.1 {
+                // Flies on, but counts as a hit rather than a miss once it expires
+                commands.entity(projectile_entity).insert(GrazedTarget);
+            } else {
                 target.last_damage_time = current_time;
                 commands.entity(projectile_entity).despawn();
                 let coin_amount = projectile.get_coin_amount();
+                let damage = scale(coin_amount, player_stats.get(Stat::Damage));
 
-                spawn_weapon_coins(
+                hits.write(TargetHit {
+                    target: target_entity,
+                    damage,
+                    position: target_transform.translation.truncate(),
+                });
+                let count = spawn_weapon_coins(
                     &mut commands,
                     target_transform.translation.truncate(),
-                    coin_amount,
+                    scale(coin_amount, player_stats.get(Stat::CoinValue)),
                     projectile.weapon_type,
                     &level_assets,
                     &mut meshes,
                     &mut materials,
                     &mut coin_materials,
+                    &mut rng,
                 );
+                coins_spawned.write(CoinsSpawned { count });
             }
         }
     }
 }
 
-/// Spawn coins based on weapon type
+/// Apply a stat multiplier to a whole amount.
+fn scale(amount: u32, multiplier: f32) -> u32 {
+    (amount as f32 * multiplier).round() as u32
+}
+
+/// Random offset of a dropped coin from the target, drawn from the coin stream.
+pub fn coin_scatter(rng: &mut GameRng) -> Vec2 {
+    use std::f32::consts::TAU;
+
+    let rng = rng.stream(RngStream::COINS);
+    let angle: f32 = rng.gen_range(0.0..TAU);
+    let distance = rng.gen_range(20.0..80.0);
+    Vec2::new(angle.cos() * distance, angle.sin() * distance)
+}
+
+/// Spawn coins based on weapon type. Returns the number of coins spawned.
 fn spawn_weapon_coins(
     commands: &mut Commands,
     position: Vec2,
@@ -402,9 +566,8 @@ fn spawn_weapon_coins(
     meshes: &mut ResMut<Assets<Mesh>>,
     _materials: &mut ResMut<Assets<ColorMaterial>>,
     coin_materials: &mut ResMut<Assets<CoinMaterial>>,
-) {
-    use std::f32::consts::TAU;
-
+    rng: &mut GameRng,
+) -> u32 {
     let coin_count = match weapon_type {
         WeaponType::Bazooka => 40,
         WeaponType::Sniper => 20,
@@ -418,11 +581,7 @@ fn spawn_weapon_coins(
     };
 
     for i in 0..coin_count {
-        let mut rng = rand::thread_rng();
-        let angle: f32 = rng.gen_range(0.0..TAU);
-        let distance = rng.gen_range(20.0..80.0);
-
-        let random_offset = Vec2::new(angle.cos() * distance, angle.sin() * distance);
+        let random_offset = coin_scatter(rng);
 
         commands.spawn((
             Name::new("Coin"),
@@ -436,15 +595,17 @@ fn spawn_weapon_coins(
             Transform::from_translation((position + random_offset).extend(1.0)),
             RigidBody::Dynamic,
             Collider::circle(8.0),
-            CollisionLayers::new(LayerMask(0b0010), LayerMask(0b0001)), // On layer 1, collides with layer 0 (ground)
+            CollisionLayers::new(LayerMask(0b0010), LayerMask(0b11101)), // On layer 1, collides with layer 0 (player), 2 (walls), 3 (platforms) and 4 (ground)
             CollisionEventsEnabled,
             LinearVelocity::ZERO, // Like player - no velocity
             GravityScale(10.0),   // Like player - no gravity
             AngularVelocity(2.0 + i as f32),
             LockedAxes::ROTATION_LOCKED, // Like player - no rotation
-            ScreenLimit,
+            StateScoped(Screen::Gameplay),
         ));
     }
+
+    coin_count
 }
 
 /// Collect coins when player touches them using collision events
@@ -454,6 +615,7 @@ fn collect_coins(
     coin_query: Query<&Coin>,
     mut money: ResMut<Money>,
     mut buffer: ResMut<CoinBuffer>,
+    mut collected: EventWriter<CoinCollected>,
     mut commands: Commands,
 ) {
     for CollisionStarted(entity1, entity2) in collision_events.read() {
@@ -466,25 +628,19 @@ fn collect_coins(
                 (None, None)
             };
 
-        if let (Some(_player), Some(coin)) = (player_entity, coin_entity) {
-            if let Ok(coin_component) = coin_query.get(coin) {
-                // Only collect if buffer has space
-                if buffer.current < buffer.max {
-                    money.amount += coin_component.value;
-                    buffer.add_coin(); // Add to buffer
-                    commands.entity(coin).despawn();
-                }
-                // If buffer is full, coin stays and doesn't disappear
+        if let (Some(_player), Some(coin)) = (player_entity, coin_entity)
+            && let Ok(coin_component) = coin_query.get(coin)
+        {
+            // Only collect if buffer has space
+            if buffer.current < buffer.max {
+                money.amount += coin_component.value;
+                buffer.add_coin(); // Add to buffer
+                collected.write(CoinCollected {
+                    value: coin_component.value,
+                });
+                commands.entity(coin).insert(CoinPickedUp).despawn();
             }
-        }
-    }
-}
-
-/// Update money display text
-fn update_money_display(money: Res<Money>, mut text_query: Query<&mut Text2d, With<ScoreText>>) {
-    if money.is_changed() {
-        for mut text in &mut text_query {
-            **text = format!("Money: ${}", money.amount);
+            // If buffer is full, coin stays and doesn't disappear
         }
     }
 }
@@ -517,31 +673,21 @@ fn spawn_continuous_laser(commands: &mut Commands, start_pos: Vec2, direction: V
 /// Update laser beam position and direction
 /// FIXME
 fn handle_laser_beam(
-    camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    player_query: Query<&Transform, With<Player>>,
+    player_query: Query<(&Transform, &AimDirection), With<Player>>,
     mut laser_query: Query<(&mut LaserBeam, &mut Transform), (With<LaserBeam>, Without<Player>)>,
 ) {
-    if let (Ok((camera, camera_transform)), Ok(window), Ok(player_transform)) = (
-        camera_query.single(),
-        window_query.single(),
-        player_query.single(),
-    ) {
-        if let Some(cursor_pos) = window.cursor_position() {
-            if let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) {
-                let player_pos = player_transform.translation.truncate();
-                let direction = (world_pos - player_pos).normalize();
+    if let Ok((player_transform, aim)) = player_query.single() {
+        let player_pos = player_transform.translation.truncate();
+        let direction = aim.direction;
 
-                for (mut laser_beam, mut transform) in &mut laser_query {
-                    laser_beam.direction = direction;
+        for (mut laser_beam, mut transform) in &mut laser_query {
+            laser_beam.direction = direction;
 
-                    let laser_center = player_pos + direction * (laser_beam.length / 2.0);
-                    transform.translation = laser_center.extend(6.0);
+            let laser_center = player_pos + direction * (laser_beam.length / 2.0);
+            transform.translation = laser_center.extend(6.0);
 
-                    let angle = direction.y.atan2(direction.x);
-                    transform.rotation = Quat::from_rotation_z(angle);
-                }
-            }
+            let angle = direction.y.atan2(direction.x);
+            transform.rotation = Quat::from_rotation_z(angle);
         }
     }
 }
@@ -552,19 +698,23 @@ fn handle_laser_beam(
 /// FIXME
 fn handle_laser_continuous_damage(
     laser_query: Query<&Transform, With<LaserBeam>>,
-    mut target_query: Query<(&Transform, &mut Target)>,
+    mut target_query: Query<(Entity, &Transform, &mut Target)>,
+    player_stats: Single<&Stats, With<Player>>,
     mut commands: Commands,
     level_assets: Res<LevelAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut coin_materials: ResMut<Assets<CoinMaterial>>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut hits: EventWriter<TargetHit>,
+    mut coins_spawned: EventWriter<CoinsSpawned>,
 ) {
     if laser_query.is_empty() {
         return;
     }
 
-    for (target_transform, mut target) in &mut target_query {
+    for (target_entity, target_transform, mut target) in &mut target_query {
         let mut is_being_hit = false;
 
         for laser_transform in &laser_query {
@@ -581,17 +731,26 @@ fn handle_laser_continuous_damage(
             target.laser_damage_timer += time.delta_secs();
             if target.laser_damage_timer >= 0.3 {
                 target.laser_damage_timer = 0.0;
+                let coin_amount = 120;
+                let damage = scale(coin_amount, player_stats.get(Stat::Damage));
 
-                spawn_weapon_coins(
+                hits.write(TargetHit {
+                    target: target_entity,
+                    damage,
+                    position: target_transform.translation.truncate(),
+                });
+                let count = spawn_weapon_coins(
                     &mut commands,
                     target_transform.translation.truncate(),
-                    120,
+                    scale(coin_amount, player_stats.get(Stat::CoinValue)),
                     WeaponType::LaserBeam,
                     &level_assets,
                     &mut meshes,
                     &mut materials,
                     &mut coin_materials,
+                    &mut rng,
                 );
+                coins_spawned.write(CoinsSpawned { count });
             }
         } else {
             target.laser_damage_timer = 0.0;
@@ -627,11 +786,11 @@ impl Material2d for CoinMaterial {
     }
 }
 
-/// Mark coins that have hit the ground for physics removal. Way better performnace that way.
+/// Mark coins that have hit the ground or a platform for physics removal. Way better performnace that way.
 fn mark_coins_for_landing(
     mut collision_events: EventReader<CollisionStarted>,
     coin_query: Query<Entity, (With<Coin>, Without<CoinLanded>, Without<CoinShouldLand>)>,
-    name_query: Query<&Name>,
+    ground_query: Query<(), Or<(With<Ground>, With<OneWayPlatform>)>>,
     mut commands: Commands,
 ) {
     for CollisionStarted(entity1, entity2) in collision_events.read() {
@@ -643,15 +802,13 @@ fn mark_coins_for_landing(
             continue;
         };
 
-        if let Ok(name) = name_query.get(ground_entity) {
-            if name.as_str() == "Invisible Ground" {
-                // Use queue to avoid panic if entity was despawned
-                commands.queue(move |world: &mut World| {
-                    if let Ok(mut entity_mut) = world.get_entity_mut(coin_entity) {
-                        entity_mut.insert(CoinShouldLand);
-                    }
-                });
-            }
+        if ground_query.contains(ground_entity) {
+            // Use queue to avoid panic if entity was despawned
+            commands.queue(move |world: &mut World| {
+                if let Ok(mut entity_mut) = world.get_entity_mut(coin_entity) {
+                    entity_mut.insert(CoinShouldLand);
+                }
+            });
         }
     }
 }
@@ -0,0 +1,334 @@
+//! Recording gameplay input and replaying it.
+//!
+//! Start the game with `--record <file>` to write every gameplay frame's actions, frame time
+//! and the RNG seed to `<file>` when the session ends. Start it with `--replay <file>` to play
+//! such a recording back: the recorded actions are fed into the player's actions, and each
+//! frame takes exactly as long as it did while recording, so bugs reproduce and demo videos
+//! look the same every time.
+//!
+//! Only the first gameplay session is recorded or replayed: both stop when it ends, including
+//! when traveling to the next level, so the recording is never overwritten by a later one.
+//!
+//! Movement, jumping, dashing, sprinting, aiming, shooting and weapon switching are replayed.
+//! Shop purchases and menus are not, so a replay should start from the same level and save
+//! as the recording.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_enhanced_input::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        player::{
+            AimDirection, Player,
+            movement::{Dash, Fire, Jump, Move, Sprint},
+        },
+        rng::GameRng,
+        shop::shop::{PlayerUpgrades, WeaponType},
+    },
+    screens::Screen,
+    toast::Toast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let args = ReplayArgs::from_env();
+    if let Some(path) = args.record {
+        app.insert_resource(Recorder {
+            path,
+            recording: None,
+        });
+    }
+    if let Some(path) = args.replay {
+        app.insert_resource(ReplaySource(path));
+    }
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (
+            start_recording.run_if(resource_exists::<Recorder>),
+            start_replay.run_if(resource_exists::<ReplaySource>),
+        ),
+    );
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        (
+            stop_recording.run_if(resource_exists::<Recorder>),
+            stop_replay.run_if(resource_exists::<Replay>),
+        ),
+    );
+    app.add_systems(
+        Last,
+        write_recording.run_if(resource_exists::<Recorder>.and(on_event::<AppExit>)),
+    );
+    app.add_systems(
+        Update,
+        (
+            record_frame.run_if(resource_exists::<Recorder>),
+            replay_frame.run_if(resource_exists::<Replay>),
+        )
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Run condition that is true while a recording is played back.
+pub fn is_replaying(replay: Option<Res<Replay>>) -> bool {
+    replay.is_some()
+}
+
+/// The input of one gameplay frame.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct InputFrame {
+    pub frame: u32,
+    /// How long the frame took.
+    pub delta_secs: f32,
+    #[serde(default)]
+    pub move_x: f32,
+    #[serde(default)]
+    pub jump: bool,
+    #[serde(default)]
+    pub dash: bool,
+    #[serde(default)]
+    pub sprint: bool,
+    #[serde(default)]
+    pub fire: bool,
+    pub aim: (f32, f32),
+    pub weapon: WeaponType,
+}
+
+/// A recorded gameplay session.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// Seed of the [`GameRng`] when the session started.
+    pub seed: u64,
+    pub frames: Vec<InputFrame>,
+}
+
+/// Paths given on the command line.
+#[derive(Default)]
+struct ReplayArgs {
+    record: Option<String>,
+    replay: Option<String>,
+}
+
+impl ReplayArgs {
+    fn from_env() -> Self {
+        let mut args = Self::default();
+        let mut env_args = std::env::args().skip(1);
+        while let Some(arg) = env_args.next() {
+            match arg.as_str() {
+                "--record" => args.record = env_args.next(),
+                "--replay" => args.replay = env_args.next(),
+                _ => {}
+            }
+        }
+        args
+    }
+}
+
+#[derive(Resource)]
+struct Recorder {
+    path: String,
+    /// The session being recorded, if in gameplay.
+    recording: Option<InputRecording>,
+}
+
+/// Recording to play back once gameplay starts.
+#[derive(Resource)]
+struct ReplaySource(String);
+
+#[derive(Resource)]
+pub struct Replay {
+    recording: InputRecording,
+    /// Index of the frame being played.
+    next: usize,
+}
+
+fn start_recording(mut recorder: ResMut<Recorder>, rng: Res<GameRng>) {
+    recorder.recording = Some(InputRecording {
+        seed: rng.seed(),
+        frames: Vec::new(),
+    });
+    info!("Recording input to {}", recorder.path);
+}
+
+fn record_frame(
+    time: Res<Time>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut recorder: ResMut<Recorder>,
+    move_action: Option<Single<&Action<Move>>>,
+    action_query: Query<(
+        &ActionState,
+        Has<Action<Jump>>,
+        Has<Action<Dash>>,
+        Has<Action<Sprint>>,
+        Has<Action<Fire>>,
+    )>,
+    player_query: Query<&AimDirection, With<Player>>,
+    upgrades: Res<PlayerUpgrades>,
+) {
+    let Some(recording) = &mut recorder.recording else {
+        return;
+    };
+    let frame = recording.frames.len() as u32;
+    if frame == 0 {
+        align_fixed_timestep(&mut fixed_time);
+    }
+
+    let mut input = InputFrame {
+        frame,
+        delta_secs: time.delta_secs(),
+        move_x: move_action.map_or(0.0, |action| action.x),
+        weapon: upgrades.current_weapon,
+        ..default()
+    };
+    for (state, jump, dash, sprint, fire) in &action_query {
+        let fired = *state == ActionState::Fired;
+        input.jump |= jump && fired;
+        input.dash |= dash && fired;
+        input.sprint |= sprint && fired;
+        input.fire |= fire && fired;
+    }
+    if let Ok(aim) = player_query.single() {
+        input.aim = aim.direction.into();
+    }
+    recording.frames.push(input);
+}
+
+fn write_recording(mut recorder: ResMut<Recorder>, mut toasts: EventWriter<Toast>) {
+    let Some(recording) = recorder.recording.take() else {
+        return;
+    };
+
+    let result = ron::to_string(&recording)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| std::fs::write(&recorder.path, contents).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => info!(
+            "Recorded {} frames to {}",
+            recording.frames.len(),
+            recorder.path
+        ),
+        Err(e) => {
+            error!("Failed to write recording to {}: {}", recorder.path, e);
+            toasts.write(Toast::error("Could not save the input recording"));
+        }
+    }
+}
+
+fn stop_recording(mut commands: Commands, recorder: ResMut<Recorder>, toasts: EventWriter<Toast>) {
+    write_recording(recorder, toasts);
+    commands.remove_resource::<Recorder>();
+}
+
+fn start_replay(mut commands: Commands, source: Res<ReplaySource>, mut toasts: EventWriter<Toast>) {
+    // The recording covers one session, so later sessions play normally.
+    commands.remove_resource::<ReplaySource>();
+    let recording = match std::fs::read_to_string(&source.0) {
+        Ok(content) => match ron::from_str::<InputRecording>(&content) {
+            Ok(recording) => recording,
+            Err(e) => {
+                error!("Failed to parse recording {}: {}", source.0, e);
+                toasts.write(Toast::error("Could not parse the input recording"));
+                return;
+            }
+        },
+        Err(e) => {
+            error!("Failed to read recording {}: {}", source.0, e);
+            toasts.write(Toast::error("Could not read the input recording"));
+            return;
+        }
+    };
+
+    info!(
+        "Replaying {} frames from {}",
+        recording.frames.len(),
+        source.0
+    );
+    commands.insert_resource(GameRng::new(recording.seed));
+    commands.insert_resource(Replay { recording, next: 0 });
+    toasts.write(Toast::info("Replaying recorded input"));
+}
+
+/// Apply the aim and weapon of this frame, and queue the actions and frame time of the next.
+///
+/// Actions are read before `Update`, so mocks inserted now take effect on the next frame.
+fn replay_frame(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    move_query: Query<Entity, With<Action<Move>>>,
+    action_query: Query<(
+        Entity,
+        Has<Action<Jump>>,
+        Has<Action<Dash>>,
+        Has<Action<Sprint>>,
+        Has<Action<Fire>>,
+    )>,
+    mut player_query: Query<&mut AimDirection, With<Player>>,
+    mut upgrades: ResMut<PlayerUpgrades>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let index = replay.next;
+    replay.next += 1;
+    if index == 0 {
+        align_fixed_timestep(&mut fixed_time);
+    }
+
+    let Some(current) = replay.recording.frames.get(index).copied() else {
+        commands.remove_resource::<Replay>();
+        *time_strategy = TimeUpdateStrategy::Automatic;
+        toasts.write(Toast::success("Replay finished"));
+        return;
+    };
+    if let Ok(mut aim) = player_query.single_mut() {
+        aim.direction = current.aim.into();
+    }
+    if upgrades.current_weapon != current.weapon {
+        upgrades.current_weapon = current.weapon;
+    }
+
+    let Some(next) = replay.recording.frames.get(index + 1).copied() else {
+        return;
+    };
+    *time_strategy = TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(next.delta_secs));
+    for entity in &move_query {
+        commands.entity(entity).insert(ActionMock::once(
+            ActionState::Fired,
+            Vec3::new(next.move_x, 0.0, 0.0),
+        ));
+    }
+    for (entity, jump, dash, sprint, fire) in &action_query {
+        let pressed = (jump && next.jump)
+            || (dash && next.dash)
+            || (sprint && next.sprint)
+            || (fire && next.fire);
+        if jump || dash || sprint || fire {
+            let state = if pressed {
+                ActionState::Fired
+            } else {
+                ActionState::None
+            };
+            commands
+                .entity(entity)
+                .insert(ActionMock::once(state, pressed));
+        }
+    }
+}
+
+fn stop_replay(mut commands: Commands, mut time_strategy: ResMut<TimeUpdateStrategy>) {
+    commands.remove_resource::<Replay>();
+    *time_strategy = TimeUpdateStrategy::Automatic;
+}
+
+/// Drop the fixed timestep's leftover time, so physics steps line up with the recording no
+/// matter how long the frames before it took.
+fn align_fixed_timestep(fixed_time: &mut Time<Fixed>) {
+    let overstep = fixed_time.overstep();
+    fixed_time.discard_overstep(overstep);
+}
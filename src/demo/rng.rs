@@ -0,0 +1,135 @@
+//! The seeded random number generator used by gameplay.
+//!
+//! Gameplay draws its randomness from [`GameRng`] instead of `thread_rng`, so a session can be
+//! reproduced from its seed. Start the game with `--seed <n>` to pick the seed; otherwise a
+//! random one is used. The seed is shown in the pause menu.
+//!
+//! Each subsystem draws from its own [`RngStream`], so adding a random roll to one of them does
+//! not shift the numbers the others get. A subsystem that needs randomness adds a stream here.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::screens::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    let seed = seed_from_env().unwrap_or_else(rand::random);
+    info!("Game seed: {}", seed);
+    app.insert_resource(GameRng::new(seed));
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_rng);
+}
+
+/// A subsystem with its own sequence of random numbers, named so its seed stays the same
+/// when other streams are added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RngStream(pub &'static str);
+
+impl RngStream {
+    /// Where coins scatter to.
+    pub const COINS: RngStream = RngStream("coins");
+}
+
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    /// Streams drawn from so far. The others start fresh from the seed when first used.
+    streams: HashMap<RngStream, StdRng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The generator of one subsystem.
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        let seed = self.seed;
+        self.streams
+            .entry(stream)
+            .or_insert_with(|| StdRng::seed_from_u64(stream_seed(seed, stream)))
+    }
+
+    /// Start every stream over from the seed.
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+}
+
+/// Derive a stream's seed from the game seed, so streams do not repeat each other's numbers.
+fn stream_seed(seed: u64, stream: RngStream) -> u64 {
+    seed ^ u64::from(crc32fast::hash(stream.0.as_bytes())).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// The seed given with `--seed <n>`, if any.
+fn seed_from_env() -> Option<u64> {
+    let mut env_args = std::env::args().skip(1);
+    while let Some(arg) = env_args.next() {
+        if arg == "--seed" {
+            let value = env_args.next().unwrap_or_default();
+            match value.parse() {
+                Ok(seed) => return Some(seed),
+                Err(e) => warn!("Ignoring invalid seed {:?}: {}", value, e),
+            }
+        }
+    }
+    None
+}
+
+/// Every run starts from the seed, so the same seed and input play out the same way.
+fn reset_rng(mut rng: ResMut<GameRng>) {
+    rng.reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::*;
+    use crate::demo::player::shooting::coin_scatter;
+
+    const OTHER: RngStream = RngStream("other");
+
+    fn scatter(rng: &mut GameRng, coins: usize) -> Vec<Vec2> {
+        (0..coins).map(|_| coin_scatter(rng)).collect()
+    }
+
+    #[test]
+    fn same_seed_scatters_coins_the_same() {
+        let first = scatter(&mut GameRng::new(42), 10);
+        assert_eq!(first, scatter(&mut GameRng::new(42), 10));
+        assert_ne!(first, scatter(&mut GameRng::new(43), 10));
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut untouched = GameRng::new(7);
+        let expected = scatter(&mut untouched, 5);
+
+        let mut drawn = GameRng::new(7);
+        for _ in 0..100 {
+            drawn.stream(OTHER).next_u64();
+        }
+        assert_eq!(scatter(&mut drawn, 5), expected);
+    }
+
+    #[test]
+    fn reset_repeats_the_sequence() {
+        let mut rng = GameRng::new(3);
+        let first = scatter(&mut rng, 5);
+        let other = rng.stream(OTHER).next_u64();
+
+        rng.reset();
+        assert_eq!(scatter(&mut rng, 5), first);
+        assert_eq!(rng.stream(OTHER).next_u64(), other);
+    }
+}
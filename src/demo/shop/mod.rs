@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+#[allow(clippy::module_inception)]
 pub mod shop;
 pub(crate) mod shop_ui;
 
@@ -2,37 +2,107 @@
 
 use crate::{
     AppSystems, PausableSystems,
+    controls::{ControlAction, Controls},
     demo::{
         level::{UpgradeShop, WeaponShop},
-        player::{Money, Player},
+        modifiers::{ModifierData, ModifierKind, Stat},
+        player::{
+            Money, Player,
+            movement::{Back, block_input_while_spawned, input_unblocked},
+        },
+        replay::is_replaying,
+        stats::ItemPurchased,
     },
+    menus::Menu,
+    toast::Toast,
 };
 use avian2d::prelude::*;
-use bevy::prelude::*;
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use bevy_enhanced_input::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::demo::shop::shop_ui::spawn_shop_ui;
 use crate::demo::shop::shop_ui::update_shop_ui;
-pub const ITEM_CONFIG_PATH: &str = "assets/configurations/";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct WeaponData {
     pub cost: u32,
     pub damage: i32,
     pub weapon_type: String,
+    /// Seconds between shots. Defaults to no limit.
+    #[serde(default)]
+    pub cooldown_secs: f32,
+    /// Stat modifiers applied while the weapon is equipped.
+    #[serde(default)]
+    pub modifiers: Vec<ModifierData>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UpgradeData {
     pub cost: u32,
     pub upgrade_type: String,
+    /// How often the upgrade can be bought. Defaults to once.
+    #[serde(default)]
+    pub max_level: Option<u32>,
+    /// Walking speed multiplier of the first level. Every further level adds the same bonus.
     #[serde(default)]
     pub player_speed_multiplier: Option<f32>,
     #[serde(default)]
+    pub acceleration_multiplier: Option<f32>,
+    #[serde(default)]
+    pub deceleration_multiplier: Option<f32>,
+    /// Stat modifiers added once per level bought.
+    #[serde(default)]
+    pub modifiers: Vec<ModifierData>,
+    #[serde(default)]
     pub buffer: Option<HashMap<String, BufferLevel>>,
 }
 
+impl UpgradeData {
+    pub fn max_level(&self) -> u32 {
+        self.max_level.unwrap_or(1)
+    }
+
+    /// The modifiers added per level, including the movement multipliers.
+    pub fn level_modifiers(&self) -> impl Iterator<Item = ModifierData> + '_ {
+        [
+            (Stat::MoveSpeed, self.player_speed_multiplier),
+            (Stat::Acceleration, self.acceleration_multiplier),
+            (Stat::Deceleration, self.deceleration_multiplier),
+        ]
+        .into_iter()
+        .filter_map(|(stat, multiplier)| {
+            multiplier.map(|multiplier| ModifierData {
+                stat,
+                kind: ModifierKind::Percent(multiplier - 1.0),
+            })
+        })
+        .chain(self.modifiers.iter().copied())
+    }
+}
+
+/// Movement of the player before any modifiers.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MovementConfig {
+    /// Walking speed, in world units per second.
+    pub base_speed: f32,
+    /// How quickly walking speed is reached, in world units per second squared.
+    pub acceleration: f32,
+    /// How quickly the player stops or turns around, in world units per second squared.
+    pub deceleration: f32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            base_speed: 400.0,
+            acceleration: 3000.0,
+            deceleration: 4000.0,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BufferLevel {
     pub buffer_amount: u32,
@@ -50,10 +120,21 @@ pub struct UpgradesConfig {
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ItemsConfig {
+    #[serde(default)]
+    pub movement: MovementConfig,
     pub weapons: WeaponsConfig,
     pub upgrades: UpgradesConfig,
 }
 
+impl ItemsConfig {
+    pub fn weapon(&self, weapon: WeaponType) -> Option<&WeaponData> {
+        self.weapons
+            .types
+            .values()
+            .find(|data| WeaponType::from_string(&data.weapon_type) == weapon)
+    }
+}
+
 #[derive(Resource)]
 pub struct ItemsData {
     pub config: ItemsConfig,
@@ -64,20 +145,22 @@ pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ShopState>();
     app.init_resource::<PlayerUpgrades>();
     app.add_systems(Startup, load_items_config);
+    block_input_while_spawned::<ShopUI>(app);
+    app.add_observer(close_shop_on_back);
     app.add_systems(
         Update,
         (
             handle_player_shop_collisions,
             handle_shop_input,
             update_shop_ui,
-            handle_weapon_switching,
+            handle_weapon_switching.run_if(input_unblocked.and(not(is_replaying))),
         )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
 }
 
-fn load_items_config(mut commands: Commands) {
+fn load_items_config(mut commands: Commands, mut toasts: EventWriter<Toast>) {
     let file_path = "assets/configurations/items.ron";
 
     match std::fs::read_to_string(file_path) {
@@ -88,10 +171,12 @@ fn load_items_config(mut commands: Commands) {
             }
             Err(e) => {
                 error!("Failed to parse items.ron: {}", e);
+                toasts.write(Toast::error("Shop items could not be loaded"));
             }
         },
         Err(e) => {
             error!("Failed to read items.ron file: {}", e);
+            toasts.write(Toast::error("Shop items could not be loaded"));
         }
     }
 }
@@ -159,94 +244,12 @@ pub struct ShopItemButton {
     pub item_type: ItemType,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct ShopItem {
-    pub name: &'static str,
-    pub item_type: ItemType,
-    pub cost: u32,
-}
-
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ItemType {
     Weapon(WeaponType),
     Upgrade(UpgradeType),
 }
 
-impl ShopItem {
-    pub fn from_name(name: &str) -> ShopItem {
-        match name {
-            "Rapid Fire" => ShopItem {
-                name: "Rapid Fire",
-                item_type: ItemType::Weapon(WeaponType::RapidFire),
-                cost: 500,
-            },
-            "Uzi" => ShopItem {
-                name: "Uzi",
-                item_type: ItemType::Weapon(WeaponType::Uzi),
-                cost: 400,
-            },
-            "Spread Shot" => ShopItem {
-                name: "Spread Shot",
-                item_type: ItemType::Weapon(WeaponType::SpreadShot),
-                cost: 750,
-            },
-            "Laser Beam" => ShopItem {
-                name: "Laser Beam",
-                item_type: ItemType::Weapon(WeaponType::LaserBeam),
-                cost: 1000,
-            },
-            "Sniper" => ShopItem {
-                name: "Sniper",
-                item_type: ItemType::Weapon(WeaponType::Sniper),
-                cost: 2000,
-            },
-            "Bazooka" => ShopItem {
-                name: "Bazooka",
-                item_type: ItemType::Weapon(WeaponType::Bazooka),
-                cost: 5000,
-            },
-            "Hammer" => ShopItem {
-                name: "Hammer",
-                item_type: ItemType::Weapon(WeaponType::Hammer),
-                cost: 3000,
-            },
-            "Sword" => ShopItem {
-                name: "Sword",
-                item_type: ItemType::Weapon(WeaponType::Sword),
-                cost: 4000,
-            },
-            "Speed Boost" => ShopItem {
-                name: "Speed Boost",
-                item_type: ItemType::Upgrade(UpgradeType::SpeedBoost),
-                cost: 300,
-            },
-            "Coin Magnet" => ShopItem {
-                name: "Coin Magnet",
-                item_type: ItemType::Upgrade(UpgradeType::CoinMagnet),
-                cost: 600,
-            },
-            "Buffer Upgrade" => ShopItem {
-                name: "Buffer Upgrade",
-                item_type: ItemType::Upgrade(UpgradeType::BufferUpgrade),
-                cost: 400,
-            },
-            _ => ShopItem {
-                name: "Rapid Fire",
-                item_type: ItemType::Weapon(WeaponType::RapidFire),
-                cost: 500,
-            },
-        }
-    }
-
-    pub fn is_weapon(&self) -> bool {
-        matches!(self.item_type, ItemType::Weapon(_))
-    }
-
-    pub fn is_upgrade(&self) -> bool {
-        matches!(self.item_type, ItemType::Upgrade(_))
-    }
-}
-
 #[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
 #[states(scoped_entities)]
 pub enum Shop {
@@ -279,6 +282,9 @@ pub struct PlayerUpgrades {
     pub speed_boost: u32,
     pub coin_magnet: bool,
     pub buffer_level: u32,
+    pub sprint: bool,
+    pub double_jump: bool,
+    pub dash: bool,
     pub current_weapon: WeaponType,
 }
 
@@ -296,47 +302,53 @@ impl Default for PlayerUpgrades {
             speed_boost: 0,
             coin_magnet: false,
             buffer_level: 1,
+            sprint: false,
+            double_jump: false,
+            dash: false,
             current_weapon: WeaponType::default(),
         }
     }
 }
 
 impl PlayerUpgrades {
-    pub fn names() -> Vec<&'static str> {
-        vec![
-            "Rapid Fire",
-            "Uzi",
-            "Spread Shot",
-            "Laser Beam",
-            "Sniper",
-            "Bazooka",
-            "Hammer",
-            "Sword",
-            "Speed Boost",
-            "Coin Magnet",
-            "Buffer Upgrade",
-        ]
+    /// How often an upgrade has been bought.
+    pub fn level(&self, upgrade: UpgradeType) -> u32 {
+        match upgrade {
+            UpgradeType::Normal => 0,
+            UpgradeType::SpeedBoost => self.speed_boost,
+            UpgradeType::CoinMagnet => self.coin_magnet as u32,
+            // The buffer starts out at level 1 without buying anything
+            UpgradeType::BufferUpgrade => self.buffer_level.saturating_sub(1),
+            UpgradeType::Sprint => self.sprint as u32,
+            UpgradeType::DoubleJump => self.double_jump as u32,
+            UpgradeType::Dash => self.dash as u32,
+        }
     }
 
-    pub fn weapon_names() -> Vec<&'static str> {
-        vec![
-            "Rapid Fire",
-            "Uzi",
-            "Spread Shot",
-            "Laser Beam",
-            "Sniper",
-            "Bazooka",
-            "Hammer",
-            "Sword",
-        ]
+    pub fn owns_weapon(&self, weapon: WeaponType) -> bool {
+        match weapon {
+            WeaponType::Normal => true,
+            WeaponType::RapidFire => self.rapid_fire,
+            WeaponType::Uzi => self.uzi,
+            WeaponType::SpreadShot => self.spread_shot,
+            WeaponType::LaserBeam => self.laser_beam,
+            WeaponType::Sniper => self.sniper,
+            WeaponType::Bazooka => self.bazooka,
+            WeaponType::Hammer => self.hammer,
+            WeaponType::Sword => self.sword,
+        }
     }
 
-    pub fn upgrade_names() -> Vec<&'static str> {
-        vec!["Speed Boost", "Coin Magnet", "Buffer Upgrade"]
+    /// Owned weapons in weapon bar order. The starting weapon is always owned.
+    pub fn owned_weapons(&self) -> Vec<WeaponType> {
+        WeaponType::ALL
+            .into_iter()
+            .filter(|weapon| self.owns_weapon(*weapon))
+            .collect()
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum WeaponType {
     #[default]
     Normal,
@@ -364,9 +376,39 @@ pub enum UpgradeType {
     SpeedBoost,
     CoinMagnet,
     BufferUpgrade,
+    Sprint,
+    DoubleJump,
+    Dash,
 }
 
 impl WeaponType {
+    pub const ALL: [WeaponType; 9] = [
+        WeaponType::Normal,
+        WeaponType::RapidFire,
+        WeaponType::Uzi,
+        WeaponType::SpreadShot,
+        WeaponType::LaserBeam,
+        WeaponType::Sniper,
+        WeaponType::Bazooka,
+        WeaponType::Hammer,
+        WeaponType::Sword,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WeaponType::Normal => "Pistol",
+            WeaponType::RapidFire => "Rapid Fire",
+            WeaponType::Uzi => "Uzi",
+            WeaponType::SpreadShot => "Spread Shot",
+            WeaponType::LaserBeam => "Laser Beam",
+            WeaponType::Sniper => "Sniper",
+            WeaponType::Bazooka => "Bazooka",
+            WeaponType::Hammer => "Hammer",
+            WeaponType::Sword => "Sword",
+        }
+    }
+
+    /// Seconds between two shots.
     pub fn from_string(s: &str) -> Self {
         match s {
             "RapidFire" => WeaponType::RapidFire,
@@ -388,6 +430,9 @@ impl UpgradeType {
             "SpeedBoost" => UpgradeType::SpeedBoost,
             "CoinMagnet" => UpgradeType::CoinMagnet,
             "BufferUpgrade" => UpgradeType::BufferUpgrade,
+            "Sprint" => UpgradeType::Sprint,
+            "DoubleJump" => UpgradeType::DoubleJump,
+            "Dash" => UpgradeType::Dash,
             _ => UpgradeType::Normal,
         }
     }
@@ -396,32 +441,47 @@ impl UpgradeType {
 /// Handle shop input and UI spawning
 fn handle_shop_input(
     shop_state: Res<ShopState>,
-    keys: Res<ButtonInput<KeyCode>>,
+    controls: Controls,
     mut commands: Commands,
     existing_ui_query: Query<Entity, With<ShopUI>>,
     items_data: Option<Res<ItemsData>>,
 ) {
-    if keys.just_pressed(KeyCode::KeyE) {
-        if shop_state.is_near_shop {
-            // Toggle shop UI
-            if existing_ui_query.is_empty() {
-                spawn_shop_ui(commands, shop_state, items_data);
-            } else {
-                // Close existing UI
-                for entity in &existing_ui_query {
-                    commands.entity(entity).despawn();
-                }
+    if controls.just_pressed(ControlAction::Interact) && shop_state.is_near_shop {
+        // Toggle shop UI
+        if existing_ui_query.is_empty() {
+            spawn_shop_ui(commands, shop_state, items_data);
+        } else {
+            // Close existing UI
+            for entity in &existing_ui_query {
+                commands.entity(entity).despawn();
             }
         }
     }
 }
 
+fn close_shop_on_back(
+    _: Trigger<Started<Back>>,
+    menu: Res<State<Menu>>,
+    shop_ui_query: Query<Entity, With<ShopUI>>,
+    mut commands: Commands,
+) {
+    // Menus opened on top of the shop are closed first
+    if *menu.get() != Menu::None {
+        return;
+    }
+    for entity in &shop_ui_query {
+        commands.entity(entity).despawn();
+    }
+}
+
 pub fn buy_item(
     trigger: Trigger<Pointer<Click>>,
     items_data: Option<Res<ItemsData>>,
     mut money: ResMut<Money>,
     mut upgrades: ResMut<PlayerUpgrades>,
     button_query: Query<(Entity, &ShopItemButton)>,
+    mut toasts: EventWriter<Toast>,
+    mut purchases: EventWriter<ItemPurchased>,
 ) {
     let Some(items_data) = items_data else {
         warn!("Items data not loaded yet");
@@ -459,16 +519,14 @@ pub fn buy_item(
         }
         ItemType::Upgrade(upgrade_type) => {
             if let Some(upgrade_data) = items_data.config.upgrades.types.get(&button.item_name) {
-                let (cost, can_buy) = match upgrade_type {
-                    UpgradeType::SpeedBoost => (upgrade_data.cost, upgrades.speed_boost < 3),
-                    UpgradeType::CoinMagnet => (upgrade_data.cost, !upgrades.coin_magnet),
+                let cost = match upgrade_type {
                     UpgradeType::BufferUpgrade => {
-                        let additional_cost = (upgrades.buffer_level.saturating_sub(1)) * 200;
-                        let total_cost = upgrade_data.cost + additional_cost;
-                        (total_cost, upgrades.buffer_level < 10)
+                        upgrade_data.cost + upgrades.level(*upgrade_type) * 200
                     }
-                    _ => (upgrade_data.cost, false),
+                    _ => upgrade_data.cost,
                 };
+                let can_buy = *upgrade_type != UpgradeType::Normal
+                    && upgrades.level(*upgrade_type) < upgrade_data.max_level();
                 (cost, can_buy)
             } else {
                 warn!("Upgrade {} not found in config", button.item_name);
@@ -479,6 +537,10 @@ pub fn buy_item(
 
     if !can_buy {
         warn!("Cannot buy item: already owned or at max level");
+        toasts.write(Toast::info(format!(
+            "{} is already owned or at max level",
+            button.item_name
+        )));
         return;
     }
 
@@ -487,10 +549,16 @@ pub fn buy_item(
             "Not enough money to buy {} (need {}, have {})",
             button.item_name, cost, money.amount
         );
+        toasts.write(Toast::error(format!(
+            "Not enough money for {} (need ${})",
+            button.item_name, cost
+        )));
         return;
     }
 
     money.amount -= cost;
+    toasts.write(Toast::success(format!("Bought {}", button.item_name)));
+    purchases.write(ItemPurchased { cost });
 
     match &button.item_type {
         ItemType::Weapon(weapon_type) => match weapon_type {
@@ -508,49 +576,43 @@ pub fn buy_item(
             UpgradeType::SpeedBoost => upgrades.speed_boost += 1,
             UpgradeType::CoinMagnet => upgrades.coin_magnet = true,
             UpgradeType::BufferUpgrade => upgrades.buffer_level += 1,
+            UpgradeType::Sprint => upgrades.sprint = true,
+            UpgradeType::DoubleJump => upgrades.double_jump = true,
+            UpgradeType::Dash => upgrades.dash = true,
             _ => {}
         },
     }
 }
 
-/// Handle weapon switching with Q/Tab keys
-fn handle_weapon_switching(keys: Res<ButtonInput<KeyCode>>, mut upgrades: ResMut<PlayerUpgrades>) {
-    if keys.just_pressed(KeyCode::KeyQ) || keys.just_pressed(KeyCode::Tab) {
-        // Get available weapons
-        let mut available_weapons = vec![WeaponType::Normal];
+/// Switch weapons with the next and previous weapon controls, the mouse wheel, or directly
+/// with the weapon slot controls
+fn handle_weapon_switching(
+    controls: Controls,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut upgrades: ResMut<PlayerUpgrades>,
+) {
+    let available_weapons = upgrades.owned_weapons();
+    let Some(current_index) = available_weapons
+        .iter()
+        .position(|&w| w == upgrades.current_weapon)
+    else {
+        return;
+    };
 
-        if upgrades.rapid_fire {
-            available_weapons.push(WeaponType::RapidFire);
-        }
-        if upgrades.uzi {
-            available_weapons.push(WeaponType::Uzi);
-        }
-        if upgrades.spread_shot {
-            available_weapons.push(WeaponType::SpreadShot);
-        }
-        if upgrades.laser_beam {
-            available_weapons.push(WeaponType::LaserBeam);
-        }
-        if upgrades.sniper {
-            available_weapons.push(WeaponType::Sniper);
-        }
-        if upgrades.bazooka {
-            available_weapons.push(WeaponType::Bazooka);
-        }
-        if upgrades.hammer {
-            available_weapons.push(WeaponType::Hammer);
-        }
-        if upgrades.sword {
-            available_weapons.push(WeaponType::Sword);
-        }
+    let scroll: f32 = mouse_wheel.read().map(|wheel| wheel.y).sum();
+    let next_index = if let Some(slot) =
+        (1..=9).position(|slot| controls.just_pressed(ControlAction::WeaponSlot(slot)))
+    {
+        slot
+    } else if controls.just_pressed(ControlAction::NextWeapon) || scroll < 0.0 {
+        (current_index + 1) % available_weapons.len()
+    } else if controls.just_pressed(ControlAction::PreviousWeapon) || scroll > 0.0 {
+        (current_index + available_weapons.len() - 1) % available_weapons.len()
+    } else {
+        return;
+    };
 
-        // Find current weapon index and switch to next
-        if let Some(current_index) = available_weapons
-            .iter()
-            .position(|&w| w == upgrades.current_weapon)
-        {
-            let next_index = (current_index + 1) % available_weapons.len();
-            upgrades.current_weapon = available_weapons[next_index];
-        }
+    if let Some(&weapon) = available_weapons.get(next_index) {
+        upgrades.current_weapon = weapon;
     }
 }
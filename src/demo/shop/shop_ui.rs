@@ -1,24 +1,12 @@
 use crate::demo::shop::shop::ShopUI;
-/// Spawn shop UI
-use crate::{
-    AppSystems, PausableSystems,
-    demo::{
-        level::{UpgradeShop, WeaponShop},
-        player::{Money, Player},
-    },
-    screens::Screen,
-};
-use avian2d::prelude::*;
-use bevy::{ecs::system::IntoObserverSystem, prelude::*};
+use bevy::prelude::*;
 
 use super::shop::{
-    ItemType, ItemsData, PlayerUpgrades, Shop, ShopItem, ShopItemButton, ShopState, UpgradeType,
-    WeaponType, buy_item,
+    ItemType, ItemsData, Shop, ShopItemButton, ShopState, UpgradeType, WeaponType, buy_item,
 };
 
 use crate::screens::Screen::Gameplay;
 use crate::theme::widget;
-use crate::theme::widget::shop_button;
 
 pub(crate) fn spawn_shop_ui(
     mut commands: Commands,
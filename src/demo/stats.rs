@@ -0,0 +1,184 @@
+//! Statistics about how a play session went.
+//!
+//! Gameplay systems report what happened through the events in this module, and
+//! [`GameStats`] adds them up. The stats are saved together with the rest of the progress.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{hud::CoinBuffer, player::Coin, shop::shop::PlayerUpgrades},
+    screens::Screen,
+};
+
+use super::shop::shop::WeaponType;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameStats>();
+    app.add_event::<ShotFired>();
+    app.add_event::<ProjectileMissed>();
+    app.add_event::<TargetHit>();
+    app.add_event::<TargetGrazed>();
+    app.add_event::<CoinsSpawned>();
+    app.add_event::<CoinCollected>();
+    app.add_event::<ItemPurchased>();
+
+    app.add_observer(count_lost_coins);
+    app.add_systems(
+        Update,
+        (
+            // Purchases can happen while the game is paused, so events are always counted.
+            record_events,
+            record_play_time.in_set(PausableSystems),
+        )
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The player pulled the trigger.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShotFired {
+    pub weapon: WeaponType,
+}
+
+/// A projectile expired without hitting anything.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ProjectileMissed;
+
+/// A weapon damaged a [`Target`](crate::demo::player::Target).
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TargetHit {
+    pub target: Entity,
+    /// Damage dealt, after the player's damage modifiers.
+    pub damage: u32,
+    pub position: Vec2,
+}
+
+/// A projectile that passed through a target on its damage cooldown expired. It counts as a
+/// hit but dealt no damage.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TargetGrazed;
+
+/// Coins were dropped by a hit target.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CoinsSpawned {
+    pub count: u32,
+}
+
+/// The player picked up a coin.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CoinCollected {
+    pub value: u32,
+}
+
+/// The player bought something in a shop.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ItemPurchased {
+    pub cost: u32,
+}
+
+/// Marks a coin that is being despawned because the player picked it up, as opposed to
+/// being lost.
+#[derive(Component)]
+pub struct CoinPickedUp;
+
+#[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameStats {
+    pub shots_fired: HashMap<WeaponType, u32>,
+    pub hits: u32,
+    pub misses: u32,
+    pub coins_spawned: u32,
+    pub coins_collected: u32,
+    pub coins_lost: u32,
+    pub money_earned: u64,
+    pub money_spent: u64,
+    /// Seconds spent holding each weapon.
+    pub time_per_weapon: HashMap<WeaponType, f32>,
+    /// Seconds spent with a full coin buffer.
+    pub buffer_full_time: f32,
+    pub items_purchased: u32,
+}
+
+impl GameStats {
+    pub fn total_shots(&self) -> u32 {
+        self.shots_fired.values().sum()
+    }
+
+    /// Share of finished shots that hit something, between 0 and 1.
+    pub fn accuracy(&self) -> f32 {
+        let finished = self.hits + self.misses;
+        if finished > 0 {
+            self.hits as f32 / finished as f32
+        } else {
+            0.0
+        }
+    }
+
+    pub fn total_play_time(&self) -> f32 {
+        self.time_per_weapon.values().sum()
+    }
+}
+
+fn record_events(
+    mut stats: ResMut<GameStats>,
+    mut shots: EventReader<ShotFired>,
+    mut misses: EventReader<ProjectileMissed>,
+    mut hits: EventReader<TargetHit>,
+    mut grazes: EventReader<TargetGrazed>,
+    mut spawned: EventReader<CoinsSpawned>,
+    mut collected: EventReader<CoinCollected>,
+    mut purchases: EventReader<ItemPurchased>,
+) {
+    for shot in shots.read() {
+        *stats.shots_fired.entry(shot.weapon).or_default() += 1;
+    }
+    stats.misses += misses.read().count() as u32;
+    stats.hits += (hits.read().count() + grazes.read().count()) as u32;
+    for coins in spawned.read() {
+        stats.coins_spawned += coins.count;
+    }
+    for coin in collected.read() {
+        stats.coins_collected += 1;
+        stats.money_earned += coin.value as u64;
+    }
+    for purchase in purchases.read() {
+        stats.items_purchased += 1;
+        stats.money_spent += purchase.cost as u64;
+    }
+}
+
+fn record_play_time(
+    time: Res<Time>,
+    upgrades: Res<PlayerUpgrades>,
+    buffer: Res<CoinBuffer>,
+    mut stats: ResMut<GameStats>,
+) {
+    let delta = time.delta_secs();
+    *stats
+        .time_per_weapon
+        .entry(upgrades.current_weapon)
+        .or_default() += delta;
+
+    if buffer.max > 0.0 && buffer.current >= buffer.max {
+        stats.buffer_full_time += delta;
+    }
+}
+
+/// Count coins that disappear from the world without being picked up. Coins cleared away
+/// when leaving gameplay or travelling to the next level don't count.
+fn count_lost_coins(
+    trigger: Trigger<OnRemove, Coin>,
+    picked_up: Query<(), With<CoinPickedUp>>,
+    screen: Res<State<Screen>>,
+    mut stats: ResMut<GameStats>,
+) {
+    // The screen has already changed by the time state scoped entities are despawned
+    if *screen.get() == Screen::Gameplay && !picked_up.contains(trigger.target()) {
+        stats.coins_lost += 1;
+    }
+}
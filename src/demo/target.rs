@@ -0,0 +1,125 @@
+//! Health of shootable targets.
+//!
+//! Targets take damage from [`TargetHit`] events. Health doesn't go below zero, and a target
+//! keeps paying out coins once it is empty.
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{player::Target, stats::TargetHit},
+    screens::Screen,
+    theme::palette::{HEALTH_BAR_BACKGROUND, HEALTH_BAR_FILL},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Health>();
+    app.init_resource::<FocusedTarget>();
+
+    app.add_observer(add_health_bar);
+    app.add_systems(OnExit(Screen::Gameplay), clear_focused_target);
+    app.add_systems(
+        Update,
+        (damage_targets, update_health_bars)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const HEALTH_BAR_SIZE: Vec2 = Vec2::new(70.0, 6.0);
+
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new(5000.0)
+    }
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The target the player damaged most recently, shown in the HUD.
+#[derive(Resource, Default)]
+pub struct FocusedTarget(pub Option<Entity>);
+
+#[derive(Component)]
+struct HealthBarFill;
+
+fn clear_focused_target(mut focused: ResMut<FocusedTarget>) {
+    focused.0 = None;
+}
+
+/// Give every target a health bar floating above it.
+fn add_health_bar(trigger: Trigger<OnAdd, Target>, mut commands: Commands) {
+    commands.entity(trigger.target()).with_child((
+        Name::new("Health Bar"),
+        Sprite::from_color(HEALTH_BAR_BACKGROUND, HEALTH_BAR_SIZE),
+        Transform::from_xyz(0.0, 48.0, 5.0),
+        children![(
+            Name::new("Health Bar Fill"),
+            HealthBarFill,
+            Sprite {
+                color: HEALTH_BAR_FILL,
+                custom_size: Some(HEALTH_BAR_SIZE),
+                anchor: Anchor::CenterLeft,
+                ..default()
+            },
+            Transform::from_xyz(-HEALTH_BAR_SIZE.x / 2.0, 0.0, 1.0),
+        )],
+    ));
+}
+
+fn damage_targets(
+    mut hits: EventReader<TargetHit>,
+    mut target_query: Query<&mut Health, With<Target>>,
+    mut focused: ResMut<FocusedTarget>,
+) {
+    for hit in hits.read() {
+        let Ok(mut health) = target_query.get_mut(hit.target) else {
+            continue;
+        };
+
+        health.current = (health.current - hit.damage as f32).max(0.0);
+        focused.0 = Some(hit.target);
+    }
+}
+
+fn update_health_bars(
+    target_query: Query<(&Health, &Children), Changed<Health>>,
+    bar_query: Query<&Children>,
+    mut fill_query: Query<&mut Sprite, With<HealthBarFill>>,
+) {
+    for (health, children) in &target_query {
+        for fill in children
+            .iter()
+            .filter_map(|bar| bar_query.get(bar).ok())
+            .flat_map(|bar_children| bar_children.iter())
+        {
+            if let Ok(mut sprite) = fill_query.get_mut(fill) {
+                sprite.custom_size = Some(Vec2::new(
+                    HEALTH_BAR_SIZE.x * health.fraction(),
+                    HEALTH_BAR_SIZE.y,
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! The weapon bar above the HUD, listing owned weapons and the state of the active one.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        player::{LaserBeam, WeaponCooldown},
+        shop::shop::{PlayerUpgrades, WeaponType},
+    },
+    screens::Screen,
+    theme::palette::{
+        HUD_TEXT, WEAPON_SLOT_ACTIVE, WEAPON_SLOT_BACKGROUND, WEAPON_SLOT_BORDER,
+        WEAPON_SLOT_COOLDOWN, WEAPON_SLOT_FIRING,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<WeaponSlot>();
+    app.register_type::<WeaponNameText>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_weapon_bar);
+    app.add_systems(
+        Update,
+        (
+            rebuild_weapon_slots.run_if(
+                resource_changed::<PlayerUpgrades>.or(any_match_filter::<Added<WeaponBar>>),
+            ),
+            update_weapon_slots,
+            fade_weapon_name,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How long the name of a weapon stays visible after switching to it, in seconds.
+const WEAPON_NAME_SECS: f32 = 1.5;
+
+#[derive(Component)]
+struct WeaponBar;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct WeaponSlot(WeaponType);
+
+#[derive(Component)]
+struct CooldownOverlay;
+
+/// Shows the name of the active weapon for a moment after switching.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct WeaponNameText(Timer);
+
+fn spawn_weapon_bar(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Weapon Bar"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Px(72.0),
+            width: Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(6.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![
+            (
+                Name::new("Weapon Name"),
+                WeaponNameText(Timer::from_seconds(WEAPON_NAME_SECS, TimerMode::Once)),
+                Text::default(),
+                TextFont::from_font_size(20.0),
+                TextColor(HUD_TEXT),
+                Pickable::IGNORE,
+            ),
+            (
+                Name::new("Weapon Slots"),
+                WeaponBar,
+                Node {
+                    column_gap: Px(6.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ),
+        ],
+    ));
+}
+
+/// Respawn the slots when weapons are bought or switched.
+fn rebuild_weapon_slots(
+    mut commands: Commands,
+    upgrades: Res<PlayerUpgrades>,
+    bar: Single<Entity, With<WeaponBar>>,
+    mut name: Single<(&mut Text, &mut WeaponNameText)>,
+    mut last_weapon: Local<Option<WeaponType>>,
+) {
+    commands
+        .entity(*bar)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            for (i, weapon) in upgrades.owned_weapons().into_iter().enumerate() {
+                parent.spawn(weapon_slot(
+                    i + 1,
+                    weapon,
+                    weapon == upgrades.current_weapon,
+                ));
+            }
+        });
+
+    if *last_weapon != Some(upgrades.current_weapon) {
+        *last_weapon = Some(upgrades.current_weapon);
+        let (text, timer) = &mut *name;
+        text.0 = upgrades.current_weapon.display_name().to_string();
+        timer.0.reset();
+    }
+}
+
+fn weapon_slot(number: usize, weapon: WeaponType, active: bool) -> impl Bundle {
+    (
+        Name::new(format!("Weapon Slot {}", number)),
+        WeaponSlot(weapon),
+        Node {
+            width: Px(52.0),
+            height: Px(52.0),
+            border: UiRect::all(Px(2.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(WEAPON_SLOT_BACKGROUND),
+        BorderColor(if active {
+            WEAPON_SLOT_ACTIVE
+        } else {
+            WEAPON_SLOT_BORDER
+        }),
+        BorderRadius::all(Px(6.0)),
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("Weapon Icon"),
+                Node {
+                    width: Px(24.0),
+                    height: Px(24.0),
+                    ..default()
+                },
+                BackgroundColor(icon_color(weapon)),
+                BorderRadius::all(Px(4.0)),
+                Pickable::IGNORE,
+            ),
+            (
+                Name::new("Slot Key"),
+                Text(number.to_string()),
+                TextFont::from_font_size(12.0),
+                TextColor(HUD_TEXT),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Px(1.0),
+                    left: Px(4.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ),
+            (
+                Name::new("Cooldown Overlay"),
+                CooldownOverlay,
+                Node {
+                    position_type: PositionType::Absolute,
+                    bottom: Px(0.0),
+                    width: Percent(100.0),
+                    height: Percent(0.0),
+                    ..default()
+                },
+                BackgroundColor(WEAPON_SLOT_COOLDOWN),
+                Pickable::IGNORE,
+            ),
+        ],
+    )
+}
+
+/// Placeholder icons until weapons get proper art. Mostly matches the projectile colors.
+fn icon_color(weapon: WeaponType) -> Color {
+    match weapon {
+        WeaponType::Normal => Color::srgb(1.0, 1.0, 0.0),
+        WeaponType::RapidFire => Color::srgb(1.0, 0.6, 0.0),
+        WeaponType::Uzi => Color::srgb(0.8, 0.8, 0.8),
+        WeaponType::SpreadShot => Color::srgb(0.6, 1.0, 0.3),
+        WeaponType::LaserBeam => Color::srgb(0.0, 1.0, 1.0),
+        WeaponType::Sniper => Color::srgb(1.0, 0.8, 0.0),
+        WeaponType::Bazooka => Color::srgb(0.9, 0.2, 0.2),
+        WeaponType::Hammer => Color::srgb(0.6, 0.4, 0.2),
+        WeaponType::Sword => Color::srgb(0.7, 0.7, 1.0),
+    }
+}
+
+/// Show the cooldown of the active weapon, and highlight the laser while it is firing.
+fn update_weapon_slots(
+    upgrades: Res<PlayerUpgrades>,
+    cooldown: Res<WeaponCooldown>,
+    laser_query: Query<(), With<LaserBeam>>,
+    mut slot_query: Query<(&WeaponSlot, &mut BorderColor, &Children)>,
+    mut overlay_query: Query<&mut Node, With<CooldownOverlay>>,
+) {
+    let firing = !laser_query.is_empty();
+    for (slot, mut border, children) in &mut slot_query {
+        let active = slot.0 == upgrades.current_weapon;
+        if active && slot.0 == WeaponType::LaserBeam {
+            border.0 = if firing {
+                WEAPON_SLOT_FIRING
+            } else {
+                WEAPON_SLOT_ACTIVE
+            };
+        }
+
+        let remaining = if active {
+            cooldown.fraction_remaining()
+        } else {
+            0.0
+        };
+        for &child in children {
+            if let Ok(mut node) = overlay_query.get_mut(child) {
+                node.height = Percent(remaining * 100.0);
+            }
+        }
+    }
+}
+
+fn fade_weapon_name(time: Res<Time>, mut name: Single<(&mut WeaponNameText, &mut TextColor)>) {
+    let (timer, color) = &mut *name;
+    timer.0.tick(time.delta());
+    // Fade out over the last half second
+    color.0.set_alpha((timer.0.remaining_secs() / 0.5).min(1.0));
+}
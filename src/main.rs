@@ -7,13 +7,16 @@
 
 mod asset_tracking;
 mod audio;
+mod controls;
 mod demo;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod menus;
+mod ron_asset;
 mod save;
 mod screens;
 mod theme;
+mod toast;
 
 use avian2d::prelude::*;
 use bevy::{
@@ -27,6 +30,7 @@ use bevy::{
     render::camera::ScalingMode,
 };
 use bevy_enhanced_input::prelude::*;
+use controls::{ControlAction, control_just_pressed};
 use iyes_perf_ui::prelude::*;
 
 fn main() -> AppExit {
@@ -63,14 +67,15 @@ impl Plugin for AppPlugin {
                 max_history_length: 1,
                 smoothing_factor: 0.0,
             },
-            EntityCountDiagnosticsPlugin::default(),
-            SystemInformationDiagnosticsPlugin::default(),
+            EntityCountDiagnosticsPlugin,
+            SystemInformationDiagnosticsPlugin,
         ));
 
         // Add other plugins.
         app.add_plugins((
             asset_tracking::plugin,
             audio::plugin,
+            controls::plugin,
             demo::plugin,
             #[cfg(feature = "dev")]
             dev_tools::plugin,
@@ -78,11 +83,15 @@ impl Plugin for AppPlugin {
             save::plugin,
             screens::plugin,
             theme::plugin,
-            PerfUiPlugin::default(),
+            toast::plugin,
+            PerfUiPlugin,
         ));
 
         // Add perf UI toggle system
-        app.add_systems(Update, toggle_perf_ui);
+        app.add_systems(
+            Update,
+            toggle_perf_ui.run_if(control_just_pressed(ControlAction::TogglePerfUi)),
+        );
 
         // Order new `AppSystems` variants by adding them here:
         app.configure_sets(
@@ -177,16 +186,11 @@ fn spawn_perf_ui(mut commands: Commands) {
     commands.spawn(PerfUiAllEntries::default());
 }
 
-fn toggle_perf_ui(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut perf_ui_query: Query<&mut Visibility, With<PerfUiRoot>>,
-) {
-    if keys.just_pressed(KeyCode::F12) {
-        for mut visibility in perf_ui_query.iter_mut() {
-            *visibility = match *visibility {
-                Visibility::Visible => Visibility::Hidden,
-                _ => Visibility::Visible,
-            };
-        }
+fn toggle_perf_ui(mut perf_ui_query: Query<&mut Visibility, With<PerfUiRoot>>) {
+    for mut visibility in perf_ui_query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Visible => Visibility::Hidden,
+            _ => Visibility::Visible,
+        };
     }
 }
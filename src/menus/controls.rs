@@ -0,0 +1,294 @@
+//! The controls menu, opened from the settings menu.
+//!
+//! Clicking a binding waits for the next key, mouse button or gamepad button and binds it.
+//! Bindings shared by several actions are shown in red.
+
+use bevy::{
+    ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+};
+
+use crate::{
+    controls::{BindingSlot, ControlAction, ControlsConfig, InputBinding},
+    demo::player::movement::back_just_pressed,
+    menus::Menu,
+    save::storage::SaveBackend,
+    theme::{palette::*, prelude::*},
+    toast::Toast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<BindingButton>();
+    app.init_resource::<RebindCapture>();
+
+    app.add_systems(OnEnter(Menu::Controls), spawn_controls_menu);
+    app.add_systems(OnExit(Menu::Controls), stop_capture);
+    app.add_observer(start_capture);
+    app.add_systems(
+        Update,
+        (
+            // A gamepad button pressed while capturing is bound, not taken as going back.
+            go_back.run_if(
+                input_just_pressed(KeyCode::Escape)
+                    .or(back_just_pressed)
+                    .and(not(capturing)),
+            ),
+            capture_binding,
+            update_binding_labels.run_if(
+                resource_changed::<ControlsConfig>
+                    .or(resource_changed::<RebindCapture>)
+                    .or(any_match_filter::<Added<BindingButton>>),
+            ),
+        )
+            .chain()
+            .run_if(in_state(Menu::Controls)),
+    );
+}
+
+/// Actions listed per column.
+const ROWS: usize = 11;
+
+/// The binding waiting for a new input, if any.
+#[derive(Resource, Default)]
+struct RebindCapture(Option<(ControlAction, BindingSlot)>);
+
+fn capturing(capture: Res<RebindCapture>) -> bool {
+    capture.0.is_some()
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BindingButton {
+    action: ControlAction,
+    slot: BindingSlot,
+}
+
+fn spawn_controls_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Controls Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::Controls),
+        children![
+            widget::header("Controls"),
+            widget::label(
+                "Click a binding, then press a key or button. Esc cancels, Delete clears."
+            ),
+            controls_grid(),
+            (
+                Name::new("Controls Buttons"),
+                Node {
+                    column_gap: Px(20.0),
+                    ..default()
+                },
+                children![
+                    widget::button("Reset", reset_to_defaults),
+                    widget::button("Back", go_back_on_click),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn controls_grid() -> impl Bundle {
+    (
+        Name::new("Controls Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(6.0),
+            column_gap: Px(10.0),
+            grid_template_columns: vec![
+                GridTrack::px(200.0),
+                GridTrack::px(130.0),
+                GridTrack::px(130.0),
+                GridTrack::px(130.0),
+                GridTrack::px(200.0),
+                GridTrack::px(130.0),
+                GridTrack::px(130.0),
+                GridTrack::px(130.0),
+            ],
+            ..default()
+        },
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            // The grid fills row by row, so interleave the two columns of actions
+            for row in 0..ROWS {
+                for action in [row, row + ROWS]
+                    .into_iter()
+                    .filter_map(|index| ControlAction::ALL.get(index))
+                {
+                    parent.spawn((
+                        widget::label(action.display_name()),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                    ));
+                    parent.spawn(binding_button(*action, BindingSlot::Keyboard));
+                    parent.spawn(binding_button(*action, BindingSlot::AltKeyboard));
+                    parent.spawn(binding_button(*action, BindingSlot::Gamepad));
+                }
+            }
+        })),
+    )
+}
+
+fn binding_button(action: ControlAction, slot: BindingSlot) -> impl Bundle {
+    (
+        Name::new("Binding Button"),
+        Button,
+        BindingButton { action, slot },
+        Node {
+            height: Px(34.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BorderRadius::all(Px(6.0)),
+        BackgroundColor(BUTTON_BACKGROUND),
+        InteractionPalette {
+            none: BUTTON_BACKGROUND,
+            hovered: BUTTON_HOVERED_BACKGROUND,
+            pressed: BUTTON_PRESSED_BACKGROUND,
+        },
+        children![(
+            Name::new("Binding Text"),
+            Text::default(),
+            TextFont::from_font_size(20.0),
+            TextColor(BUTTON_TEXT),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+fn start_capture(
+    trigger: Trigger<Pointer<Click>>,
+    button_query: Query<&BindingButton>,
+    mut capture: ResMut<RebindCapture>,
+) {
+    if let Ok(button) = button_query.get(trigger.target()) {
+        capture.0 = Some((button.action, button.slot));
+    }
+}
+
+fn stop_capture(mut capture: ResMut<RebindCapture>) {
+    capture.0 = None;
+}
+
+/// Bind the first input pressed while waiting for one.
+fn capture_binding(
+    mut capture: ResMut<RebindCapture>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut config: ResMut<ControlsConfig>,
+    storage: Res<SaveBackend>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let Some((action, slot)) = capture.0 else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        capture.0 = None;
+        return;
+    }
+    let binding = if keys.just_pressed(KeyCode::Delete) {
+        None
+    } else {
+        let pressed = match slot {
+            BindingSlot::Keyboard | BindingSlot::AltKeyboard => keys
+                .get_just_pressed()
+                .next()
+                .map(|key| InputBinding::Key(*key))
+                .or_else(|| {
+                    mouse
+                        .get_just_pressed()
+                        .next()
+                        .map(|button| InputBinding::Mouse(*button))
+                }),
+            BindingSlot::Gamepad => gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next())
+                .map(|button| InputBinding::Gamepad(*button)),
+        };
+        if pressed.is_none() {
+            return;
+        }
+        pressed
+    };
+
+    capture.0 = None;
+    config.set(action, slot, binding);
+
+    if let Some(binding) = binding
+        && let Some(other) = config
+            .actions_bound_to(binding)
+            .find(|other| *other != action)
+    {
+        toasts.write(Toast::info(format!(
+            "{} is also bound to {}",
+            binding.display_name(),
+            other.display_name()
+        )));
+    }
+
+    if let Err(e) = config.save(&**storage) {
+        error!("Failed to save controls: {}", e);
+        toasts.write(Toast::error("Could not save controls"));
+    }
+}
+
+fn reset_to_defaults(
+    _: Trigger<Pointer<Click>>,
+    mut config: ResMut<ControlsConfig>,
+    storage: Res<SaveBackend>,
+    mut toasts: EventWriter<Toast>,
+) {
+    *config = ControlsConfig::default();
+    match config.save(&**storage) {
+        Ok(()) => {
+            toasts.write(Toast::success("Controls reset to defaults"));
+        }
+        Err(e) => {
+            error!("Failed to save controls: {}", e);
+            toasts.write(Toast::error("Could not save controls"));
+        }
+    }
+}
+
+fn update_binding_labels(
+    config: Res<ControlsConfig>,
+    capture: Res<RebindCapture>,
+    button_query: Query<(&BindingButton, &Children)>,
+    mut text_query: Query<(&mut Text, &mut TextColor)>,
+) {
+    for (button, children) in &button_query {
+        let Some((mut text, mut color)) = children
+            .first()
+            .and_then(|child| text_query.get_mut(*child).ok())
+        else {
+            continue;
+        };
+
+        let binding = config.get(button.action).get(button.slot);
+        text.0 = if capture.0 == Some((button.action, button.slot)) {
+            "...".to_string()
+        } else {
+            binding.map_or("-".to_string(), InputBinding::display_name)
+        };
+
+        let conflicting =
+            binding.is_some_and(|binding| config.actions_bound_to(binding).count() > 1);
+        color.0 = if conflicting {
+            BINDING_CONFLICT_TEXT
+        } else {
+            BUTTON_TEXT
+        };
+    }
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
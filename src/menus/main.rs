@@ -2,39 +2,69 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    demo::levels::{CurrentLevel, CurrentLevelAssets},
+    menus::Menu,
+    save::{LoadRequest, latest_save, storage::SaveBackend},
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
-fn spawn_main_menu(mut commands: Commands) {
-    commands.spawn((
-        widget::ui_root("Main Menu"),
-        GlobalZIndex(2),
-        StateScoped(Menu::Main),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-        ],
-    ));
+fn spawn_main_menu(mut commands: Commands, storage: Res<SaveBackend>) {
+    let has_saves = latest_save(&**storage).is_some();
+
+    commands
+        .spawn((
+            widget::ui_root("Main Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Main),
+        ))
+        .with_children(|parent| {
+            if has_saves {
+                parent.spawn(widget::button("Continue", continue_latest_save));
+            }
+            parent.spawn(widget::button("Play", enter_loading_or_gameplay_screen));
+            if has_saves {
+                parent.spawn(widget::button("Load Game", open_load_menu));
+            }
+            parent.spawn(widget::button("Settings", open_settings_menu));
+            parent.spawn(widget::button("Credits", open_credits_menu));
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button("Exit", exit_app));
+        });
+}
+
+/// Load the most recent save. The save system takes us into gameplay once it's loaded.
+fn continue_latest_save(
+    _: Trigger<Pointer<Click>>,
+    storage: Res<SaveBackend>,
+    mut commands: Commands,
+) {
+    if let Some(key) = latest_save(&**storage) {
+        commands.insert_resource(LoadRequest::Slot(key));
+    }
+}
+
+fn open_load_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveLoad);
 }
 
 fn enter_loading_or_gameplay_screen(
     _: Trigger<Pointer<Click>>,
     resource_handles: Res<ResourceHandles>,
+    asset_server: Res<AssetServer>,
+    level: Res<State<CurrentLevel>>,
+    level_assets: Option<Res<CurrentLevelAssets>>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
-    if resource_handles.is_all_done() {
+    let level_loaded = level_assets
+        .is_some_and(|level_assets| level_assets.is_loaded(*level.get(), &asset_server));
+    if resource_handles.is_all_done() && level_loaded {
         next_screen.set(Screen::Gameplay);
     } else {
         next_screen.set(Screen::Loading);
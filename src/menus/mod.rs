@@ -1,9 +1,11 @@
 //! The game's menus and transitions between them.
 
+mod controls;
 mod credits;
 mod main;
 mod pause;
 mod settings;
+mod stats;
 
 use bevy::prelude::*;
 
@@ -11,10 +13,12 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        controls::plugin,
         credits::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
+        stats::plugin,
     ));
 
     // Add save/load menu handling
@@ -32,6 +36,8 @@ pub enum Menu {
     Main,
     Credits,
     Settings,
+    Controls,
     Pause,
     SaveLoad,
+    Stats,
 }
@@ -2,17 +2,24 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    demo::{player::movement::back_just_pressed, rng::GameRng},
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(
+            in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape).or(back_just_pressed)),
+        ),
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands) {
+fn spawn_pause_menu(mut commands: Commands, rng: Res<GameRng>) {
     commands.spawn((
         widget::ui_root("Pause Menu"),
         GlobalZIndex(2),
@@ -21,8 +28,10 @@ fn spawn_pause_menu(mut commands: Commands) {
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
             widget::button("Save / Load", open_save_load_menu),
+            widget::button("Statistics", open_stats_menu),
             widget::button("Settings", open_settings_menu),
             widget::button("Quit to title", quit_to_title),
+            widget::label(format!("Seed: {}", rng.seed())),
         ],
     ));
 }
@@ -35,6 +44,10 @@ fn open_save_load_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextSta
     next_menu.set(Menu::SaveLoad);
 }
 
+fn open_stats_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Stats);
+}
+
 fn close_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
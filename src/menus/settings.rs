@@ -3,31 +3,47 @@
 //! Additional settings and accessibility options should go here.
 
 use bevy::{
-    audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
-    window::PresentMode,
+    audio::Volume,
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    ui::Val::*,
+    window::{PresentMode, PrimaryWindow},
 };
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    demo::{floating_text::FloatingTextSettings, player::movement::back_just_pressed},
+    menus::Menu,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(
+            in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape).or(back_just_pressed)),
+        ),
     );
 
     app.register_type::<GlobalVolumeLabel>();
     app.register_type::<FramerateLimitLabel>();
+    app.register_type::<UiScaleLabel>();
+    app.register_type::<FloatingTextLabel>();
     app.init_resource::<FramerateLimitSettings>();
+    app.init_resource::<UiScaleSetting>();
     app.add_systems(
         Update,
         (
             update_global_volume_label,
             update_framerate_limit_label,
             apply_framerate_limit_changes,
+            update_ui_scale_label,
+            update_floating_text_label,
         )
             .run_if(in_state(Menu::Settings)),
     );
+    app.add_systems(Update, apply_ui_scale);
 }
 
 fn spawn_settings_menu(mut commands: Commands) {
@@ -38,6 +54,7 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::button("Controls", open_controls_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -70,6 +87,22 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             framerate_limit_widget(),
+            (
+                widget::label("UI Scale"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            ui_scale_widget(),
+            (
+                widget::label("Damage Numbers"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            floating_text_widget(),
         ],
     )
 }
@@ -122,6 +155,10 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+fn open_controls_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
+}
+
 fn go_back_on_click(
     _: Trigger<Pointer<Click>>,
     screen: Res<State<Screen>>,
@@ -162,7 +199,7 @@ impl FramerateOption {
         }
     }
 
-    fn to_present_mode(&self) -> PresentMode {
+    fn to_present_mode(self) -> PresentMode {
         match self {
             FramerateOption::Unlimited => PresentMode::Immediate,
             FramerateOption::Fps30 => PresentMode::Fifo,
@@ -281,3 +318,121 @@ fn apply_framerate_limit_changes(
         }
     }
 }
+
+/// The player's preferred UI size, on top of scaling with the window.
+#[derive(Resource)]
+struct UiScaleSetting(f32);
+
+impl Default for UiScaleSetting {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 2.0;
+/// Window height at which the UI is drawn at its nominal size.
+const UI_REFERENCE_HEIGHT: f32 = 720.0;
+
+fn ui_scale_widget() -> impl Bundle {
+    (
+        Name::new("UI Scale Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_ui_scale),
+            (
+                Name::new("Current UI Scale"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), UiScaleLabel)],
+            ),
+            widget::button_small("+", raise_ui_scale),
+        ],
+    )
+}
+
+fn lower_ui_scale(_: Trigger<Pointer<Click>>, mut setting: ResMut<UiScaleSetting>) {
+    setting.0 = (setting.0 - 0.1).max(MIN_UI_SCALE);
+}
+
+fn raise_ui_scale(_: Trigger<Pointer<Click>>, mut setting: ResMut<UiScaleSetting>) {
+    setting.0 = (setting.0 + 0.1).min(MAX_UI_SCALE);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct UiScaleLabel;
+
+fn update_ui_scale_label(
+    setting: Res<UiScaleSetting>,
+    mut label: Single<&mut Text, With<UiScaleLabel>>,
+) {
+    let percent = 100.0 * setting.0;
+    label.0 = format!("{percent:3.0}%");
+}
+
+/// Scale the whole UI with the window height and the player's UI scale setting.
+fn apply_ui_scale(
+    setting: Res<UiScaleSetting>,
+    window: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+    if !setting.is_changed() && !window.is_changed() {
+        return;
+    }
+
+    let window_scale = (window.height() / UI_REFERENCE_HEIGHT).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    let scale = setting.0 * window_scale;
+    // The window also changes whenever the cursor moves, so avoid relayouting needlessly.
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+fn floating_text_widget() -> impl Bundle {
+    (
+        Name::new("Damage Numbers Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", toggle_floating_text),
+            (
+                Name::new("Current Damage Numbers"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    min_width: Px(80.0),
+                    ..default()
+                },
+                children![(widget::label(""), FloatingTextLabel)],
+            ),
+            widget::button_small(">", toggle_floating_text),
+        ],
+    )
+}
+
+fn toggle_floating_text(_: Trigger<Pointer<Click>>, mut settings: ResMut<FloatingTextSettings>) {
+    settings.enabled = !settings.enabled;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FloatingTextLabel;
+
+fn update_floating_text_label(
+    settings: Res<FloatingTextSettings>,
+    mut label: Single<&mut Text, With<FloatingTextLabel>>,
+) {
+    label.0 = if settings.enabled { "On" } else { "Off" }.to_string();
+}
@@ -0,0 +1,122 @@
+//! The statistics menu, opened from the pause menu.
+
+use bevy::{
+    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+};
+
+use crate::{
+    demo::{player::movement::back_just_pressed, stats::GameStats},
+    menus::Menu,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Stats), spawn_stats_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(
+            in_state(Menu::Stats).and(input_just_pressed(KeyCode::Escape).or(back_just_pressed)),
+        ),
+    );
+}
+
+fn spawn_stats_menu(mut commands: Commands, stats: Res<GameStats>) {
+    commands.spawn((
+        widget::ui_root("Statistics Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::Stats),
+        children![
+            widget::header("Statistics"),
+            grid(overview(&stats)),
+            widget::header("Time per weapon"),
+            grid(time_per_weapon(&stats)),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn overview(stats: &GameStats) -> Vec<[String; 2]> {
+    vec![
+        ["Play time".into(), format_duration(stats.total_play_time())],
+        ["Shots fired".into(), stats.total_shots().to_string()],
+        ["Hits".into(), stats.hits.to_string()],
+        ["Misses".into(), stats.misses.to_string()],
+        [
+            "Accuracy".into(),
+            format!("{:.0}%", stats.accuracy() * 100.0),
+        ],
+        [
+            "Coins collected".into(),
+            format!("{} / {}", stats.coins_collected, stats.coins_spawned),
+        ],
+        ["Coins lost".into(), stats.coins_lost.to_string()],
+        ["Money earned".into(), format!("${}", stats.money_earned)],
+        ["Money spent".into(), format!("${}", stats.money_spent)],
+        ["Items purchased".into(), stats.items_purchased.to_string()],
+        [
+            "Time with a full buffer".into(),
+            format_duration(stats.buffer_full_time),
+        ],
+    ]
+}
+
+fn time_per_weapon(stats: &GameStats) -> Vec<[String; 2]> {
+    let mut weapons: Vec<_> = stats.time_per_weapon.iter().collect();
+    weapons.sort_by(|a, b| b.1.total_cmp(a.1));
+    weapons
+        .into_iter()
+        .map(|(weapon, seconds)| {
+            let shots = stats.shots_fired.get(weapon).copied().unwrap_or(0);
+            [
+                format!("{:?}", weapon),
+                format!("{} ({} shots)", format_duration(*seconds), shots),
+            ]
+        })
+        .collect()
+}
+
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn grid(content: Vec<[String; 2]>) -> impl Bundle {
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(10.0),
+            column_gap: Px(30.0),
+            grid_template_columns: RepeatedGridTrack::px(2, 300.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(content.into_iter().flatten().enumerate().map(
+            |(i, text)| {
+                (
+                    widget::label(text),
+                    Node {
+                        justify_self: if i.is_multiple_of(2) {
+                            JustifySelf::End
+                        } else {
+                            JustifySelf::Start
+                        },
+                        ..default()
+                    },
+                )
+            },
+        ))),
+    )
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
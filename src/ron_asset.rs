@@ -0,0 +1,77 @@
+//! Loading RON configuration files through the asset server.
+//!
+//! Reading `assets/` with `std::fs` doesn't work on the web build, so configuration files are
+//! loaded as assets instead. Each config type gets its own loader for `.ron` files, and the
+//! asset server picks the right one from the type a file is loaded as.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+
+pub trait RonAssetApp {
+    /// Register `T` as an asset loaded from a `.ron` file.
+    fn init_ron_asset<T: Asset + DeserializeOwned>(&mut self) -> &mut Self;
+}
+
+impl RonAssetApp for App {
+    fn init_ron_asset<T: Asset + DeserializeOwned>(&mut self) -> &mut Self {
+        self.init_asset::<T>()
+            .register_asset_loader(RonAssetLoader::<T>(PhantomData))
+    }
+}
+
+struct RonAssetLoader<T>(PhantomData<fn() -> T>);
+
+#[derive(Debug)]
+pub enum RonAssetError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for RonAssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RonAssetError::Io(e) => write!(f, "Could not read file: {}", e),
+            RonAssetError::Parse(e) => write!(f, "Could not parse file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RonAssetError {}
+
+impl From<std::io::Error> for RonAssetError {
+    fn from(e: std::io::Error) -> Self {
+        RonAssetError::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for RonAssetError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        RonAssetError::Parse(e)
+    }
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = RonAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<T>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
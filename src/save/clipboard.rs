@@ -0,0 +1,30 @@
+//! Just enough system clipboard access to copy and paste save codes.
+
+/// Put `text` on the system clipboard.
+#[cfg(not(target_family = "wasm"))]
+pub fn copy(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| e.to_string())
+}
+
+/// Read text from the system clipboard.
+#[cfg(not(target_family = "wasm"))]
+pub fn paste() -> Option<String> {
+    arboard::Clipboard::new().ok()?.get_text().ok()
+}
+
+/// Put `text` on the system clipboard. The browser completes the write asynchronously.
+#[cfg(target_family = "wasm")]
+pub fn copy(text: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("no browser window")?;
+    let _ = window.navigator().clipboard().write_text(text);
+    Ok(())
+}
+
+/// Browsers only allow reading the clipboard asynchronously, so pasting isn't supported there.
+/// Codes can still be typed into the import field.
+#[cfg(target_family = "wasm")]
+pub fn paste() -> Option<String> {
+    None
+}
@@ -0,0 +1,221 @@
+//! Corruption-safe save files.
+//!
+//! Saves are wrapped in a small envelope carrying a CRC32 of the payload and handed to the
+//! [`SaveStorage`] backend, which never leaves a half-written save behind. The previous
+//! contents of a slot are kept as `<slot>.bak`.
+//!
+//! Saves can also be exported as a single line of text so players can carry their progress
+//! to another machine or the web build. See [`export`] for the format.
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+use super::storage::SaveStorage;
+
+/// Why a save file could not be turned back into save data.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    ChecksumMismatch { expected: u32, actual: u32 },
+    InvalidCode,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not read file: {}", e),
+            SaveError::Parse(e) => write!(f, "could not parse save data: {}", e),
+            SaveError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch (expected {:08x}, got {:08x})",
+                expected, actual
+            ),
+            SaveError::InvalidCode => write!(f, "not a valid save code"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Parse(e)
+    }
+}
+
+/// Key of the rolling backup kept next to a save slot.
+pub fn backup_key(key: &str) -> String {
+    format!("{}.bak", key)
+}
+
+/// Wrap save data in an envelope containing its checksum.
+pub fn encode(data: &serde_json::Value) -> String {
+    let payload = data.to_string();
+    serde_json::json!({
+        "checksum": crc32fast::hash(payload.as_bytes()),
+        "data": data,
+    })
+    .to_string()
+}
+
+/// Unwrap and verify an envelope produced by [`encode`].
+///
+/// Saves written before checksums were introduced have no envelope and are accepted as is.
+pub fn decode(contents: &str) -> Result<serde_json::Value, SaveError> {
+    let value = serde_json::from_str::<serde_json::Value>(contents)?;
+
+    let (Some(expected), Some(data)) = (value["checksum"].as_u64(), value.get("data")) else {
+        return Ok(value);
+    };
+
+    let expected = expected as u32;
+    let actual = crc32fast::hash(data.to_string().as_bytes());
+    if expected != actual {
+        return Err(SaveError::ChecksumMismatch { expected, actual });
+    }
+
+    Ok(data.clone())
+}
+
+/// Store `contents` under `key`, moving the slot's previous contents to its backup first.
+///
+/// A corrupt slot is not rotated, so it can't overwrite the last good backup.
+pub fn write_with_backup(
+    storage: &dyn SaveStorage,
+    key: &str,
+    contents: &str,
+) -> std::io::Result<()> {
+    let previous = storage
+        .read(key)
+        .ok()
+        .filter(|previous| decode(previous).is_ok());
+    if let Some(previous) = previous {
+        storage.write(&backup_key(key), &previous)?;
+    }
+    storage.write(key, contents)
+}
+
+/// Read and verify a save file.
+pub fn read_verified(storage: &dyn SaveStorage, key: &str) -> Result<serde_json::Value, SaveError> {
+    let contents = storage.read(key)?;
+    decode(&contents)
+}
+
+/// Prefix of exported saves, bumped whenever the format changes.
+const EXPORT_PREFIX: &str = "WTKB1";
+
+/// Encode save data as a shareable string: `WTKB1.<base64 payload>.<crc32 hex>`.
+pub fn export(data: &serde_json::Value) -> String {
+    let payload = data.to_string();
+    format!(
+        "{}.{}.{:08x}",
+        EXPORT_PREFIX,
+        URL_SAFE_NO_PAD.encode(payload.as_bytes()),
+        crc32fast::hash(payload.as_bytes())
+    )
+}
+
+/// Decode and verify a string produced by [`export`].
+///
+/// Whitespace is ignored, so codes that were wrapped over several lines still import.
+pub fn import(code: &str) -> Result<serde_json::Value, SaveError> {
+    let code: String = code.split_whitespace().collect();
+    let mut parts = code.split('.');
+    let (Some(EXPORT_PREFIX), Some(payload), Some(checksum), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(SaveError::InvalidCode);
+    };
+
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| SaveError::InvalidCode)?;
+    let expected = u32::from_str_radix(checksum, 16).map_err(|_| SaveError::InvalidCode)?;
+    let actual = crc32fast::hash(&payload);
+    if expected != actual {
+        return Err(SaveError::ChecksumMismatch { expected, actual });
+    }
+
+    let data = serde_json::from_slice::<serde_json::Value>(&payload)?;
+    if !data["money"].is_u64() || !data["upgrades"].is_object() {
+        return Err(SaveError::InvalidCode);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn export_round_trips() {
+        let data = json!({ "money": 120, "upgrades": { "uzi": true }, "level": "meadow" });
+        assert_eq!(import(&export(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn import_ignores_whitespace() {
+        let data = json!({ "money": 5, "upgrades": {} });
+        let code = export(&data);
+        let (head, tail) = code.split_at(code.len() / 2);
+        assert_eq!(import(&format!(" {}\n{} ", head, tail)).unwrap(), data);
+    }
+
+    #[test]
+    fn import_rejects_bad_codes() {
+        let code = export(&json!({ "money": 5, "upgrades": {} }));
+
+        assert!(matches!(import(""), Err(SaveError::InvalidCode)));
+        assert!(matches!(
+            import(&code.replacen("WTKB1", "WTKB0", 1)),
+            Err(SaveError::InvalidCode)
+        ));
+        assert!(matches!(
+            import(&format!("{}.extra", code)),
+            Err(SaveError::InvalidCode)
+        ));
+        assert!(matches!(
+            import("WTKB1.not base64!.00000000"),
+            Err(SaveError::InvalidCode)
+        ));
+
+        // Flip the checksum
+        let (body, checksum) = code.rsplit_once('.').unwrap();
+        let checksum = u32::from_str_radix(checksum, 16).unwrap() ^ 1;
+        assert!(matches!(
+            import(&format!("{}.{:08x}", body, checksum)),
+            Err(SaveError::ChecksumMismatch { .. })
+        ));
+
+        // Valid checksum, but not save data
+        for data in [
+            json!({ "upgrades": {} }),
+            json!({ "money": 5 }),
+            json!([1, 2]),
+        ] {
+            assert!(matches!(
+                import(&export(&data)),
+                Err(SaveError::InvalidCode)
+            ));
+        }
+    }
+
+    #[test]
+    fn decode_rejects_tampered_saves() {
+        let mut envelope: serde_json::Value =
+            serde_json::from_str(&encode(&json!({ "money": 5 }))).unwrap();
+        envelope["data"]["money"] = json!(5000);
+        assert!(matches!(
+            decode(&envelope.to_string()),
+            Err(SaveError::ChecksumMismatch { .. })
+        ));
+    }
+}
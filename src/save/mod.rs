@@ -1,14 +1,27 @@
 use bevy::prelude::*;
 use moonshine_save::prelude::*;
 
+mod clipboard;
+pub mod integrity;
+pub mod storage;
 pub mod ui;
 
-const SAVES_DIR: &str = "saves";
+use crate::{
+    demo::{
+        levels::{CurrentLevel, LevelData, LevelRegistry},
+        player::movement::{back_just_pressed, block_input_while_spawned},
+        stats::GameStats,
+    },
+    menus::Menu,
+    screens::Screen,
+    toast::Toast,
+};
+use storage::SaveBackend;
 
 /// Plugin that handles save/load functionality using moonshine-save
 pub fn plugin(app: &mut App) {
     app.add_plugins((SavePlugin, LoadPlugin))
-        .init_resource::<SaveSettings>()
+        .insert_resource(SaveBackend::platform_default())
         .add_event::<DeleteSaveEvent>()
         .register_type::<crate::demo::player::shooting::Money>()
         .register_type::<crate::demo::shop::shop::PlayerUpgrades>()
@@ -16,14 +29,24 @@ pub fn plugin(app: &mut App) {
         .add_systems(Startup, setup_save_system)
         .add_systems(
             Update,
-            (
-                handle_delete_events,
-                show_save_notifications,
-                ui::save_ui_system,
-            )
-                .run_if(in_state(crate::screens::Screen::Gameplay)),
+            (handle_delete_events, ui::save_ui_system).run_if(in_state(Screen::Gameplay)),
         )
-        .add_systems(Update, (handle_save_requests, handle_load_requests));
+        .add_systems(
+            Update,
+            // Saves refer to levels by id, which the registry resolves
+            (handle_save_requests, handle_load_requests).run_if(resource_exists::<LevelRegistry>),
+        )
+        .add_systems(
+            Update,
+            (ui::edit_save_code_field, ui::update_save_code_field_text)
+                .chain()
+                .run_if(in_state(Menu::SaveLoad)),
+        )
+        .add_systems(
+            Update,
+            ui::go_back.run_if(in_state(Menu::SaveLoad).and(back_just_pressed)),
+        );
+    block_input_while_spawned::<ui::SaveLoadMenu>(app);
 }
 
 /// Marker component for entities that should be saved
@@ -31,31 +54,28 @@ pub fn plugin(app: &mut App) {
 #[reflect(Component)]
 pub struct GameSave;
 
-/// Save system configuration
-#[derive(Resource, Default)]
-pub struct SaveSettings {
-    pub quick_save_enabled: bool,
-}
-
 /// Resource-based save request (moonshine-save style)
 #[derive(Resource)]
 pub struct SaveRequest {
-    pub path: String,
+    pub key: String,
     pub slot_name: String,
 }
 
 /// Resource-based load request (moonshine-save style)
 #[derive(Resource)]
-pub struct LoadRequest {
-    pub path: String,
+pub enum LoadRequest {
+    /// Load a save slot from storage
+    Slot(String),
+    /// Load a save code produced by [`integrity::export`]
+    Import(String),
 }
 
-fn get_save_path(filename: &str) -> String {
-    if let Err(e) = std::fs::create_dir_all(SAVES_DIR) {
-        warn!("Failed to create saves directory: {}", e);
-    }
+/// Storage key of the quick save slot
+pub const QUICKSAVE_KEY: &str = "quicksave.ron";
 
-    format!("{}/{}", SAVES_DIR, filename)
+/// Storage key of a numbered save slot
+pub fn slot_key(slot_id: u32) -> String {
+    format!("save_{:03}.ron", slot_id)
 }
 
 #[derive(Event)]
@@ -64,201 +84,119 @@ pub struct DeleteSaveEvent {
 }
 
 // Simplified - just handle file deletion directly
-pub fn handle_delete_events(mut delete_events: EventReader<DeleteSaveEvent>) {
+pub fn handle_delete_events(
+    mut delete_events: EventReader<DeleteSaveEvent>,
+    storage: Res<SaveBackend>,
+) {
     for event in delete_events.read() {
-        let filename = format!("save_{:03}.ron", event.slot_id);
-        let full_path = get_save_path(&filename);
-        if let Err(e) = std::fs::remove_file(&full_path) {
-            warn!("Failed to delete save file {}: {}", full_path, e);
+        let key = slot_key(event.slot_id);
+        if let Err(e) = storage.remove(&key) {
+            warn!("Failed to delete save file {}: {}", key, e);
         } else {
-            info!("Deleted save file: {}", full_path);
+            info!("Deleted save file: {}", key);
         }
-    }
-}
-
-/// Quick save system for Box Slayer
-pub fn quick_save(mut commands: Commands) {
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    commands.insert_resource(SaveRequest {
-        path: get_save_path("quicksave.ron"),
-        slot_name: format!("Quick Save - {}", timestamp),
-    });
-
-    info!("Quick save created");
-}
-
-/// Helper function to mark entities as saveable
-pub fn mark_as_saveable(mut commands: Commands, query: Query<Entity>) {
-    for entity in query.iter() {
-        commands.entity(entity).insert(Save);
+        // The backup may legitimately not exist yet
+        let _ = storage.remove(&integrity::backup_key(&key));
     }
 }
 
 /// Helper function to initialize save system
 pub fn setup_save_system(mut commands: Commands) {
-    commands.insert_resource(SaveSettings {
-        quick_save_enabled: true,
-    });
-    commands.insert_resource(SaveNotifications::default());
-
     // Spawn a dummy entity marked for saving to prevent moonshine-save panics
     // This ensures there's always at least one entity to save
     commands.spawn((GameSave, Save, Name::new("SaveSystemMarker")));
 }
 
-/// Resource for tracking save notifications
-#[derive(Resource, Default)]
-pub struct SaveNotifications {
-    pub notifications: Vec<SaveNotification>,
-}
-
-#[derive(Clone)]
-pub struct SaveNotification {
-    pub message: String,
-    pub timestamp: f64,
-    pub duration: f64,
-}
-
-/// Simple notification system - shows feedback when save/load resources are detected
-pub fn show_save_notifications(
-    mut save_notifications: ResMut<SaveNotifications>,
-    save_request: Option<Res<SaveRequest>>,
-    load_request: Option<Res<LoadRequest>>,
-    time: Res<Time>,
-) {
-    // Add notifications when save/load requests are detected
-    if let Some(request) = save_request {
-        if request.is_added() {
-            save_notifications.notifications.push(SaveNotification {
-                message: format!("Saving: {}", request.slot_name),
-                timestamp: time.elapsed_secs_f64(),
-                duration: 2.0,
-            });
-        }
-    }
-
-    if let Some(_request) = load_request {
-        if _request.is_added() {
-            save_notifications.notifications.push(SaveNotification {
-                message: "Loading game...".to_string(),
-                timestamp: time.elapsed_secs_f64(),
-                duration: 2.0,
-            });
-        }
-    }
-
-    // Remove expired notifications
-    let current_time = time.elapsed_secs_f64();
-    save_notifications
-        .notifications
-        .retain(|notification| current_time - notification.timestamp < notification.duration);
-}
-
 #[derive(Clone, Debug)]
 pub struct SaveFileInfo {
-    pub path: String,
+    pub key: String,
     pub display_name: String,
     pub timestamp: String,
-    pub turn: Option<u32>,
 }
 
 /// Get list of all save files with metadata
-pub fn get_save_files() -> Vec<SaveFileInfo> {
+pub fn get_save_files(storage: &dyn storage::SaveStorage) -> Vec<SaveFileInfo> {
     let mut save_files = Vec::new();
 
-    // Create saves directory if it doesn't exist
-    if let Err(e) = std::fs::create_dir_all(SAVES_DIR) {
-        warn!("Failed to create saves directory: {}", e);
-        return save_files;
-    }
-
-    if let Ok(entries) = std::fs::read_dir(SAVES_DIR) {
-        for entry in entries.flatten() {
-            if let Some(filename) = entry.file_name().to_str() {
-                if filename.ends_with(".ron")
-                    && (filename.starts_with("save_")
-                        || filename.starts_with("autosave_")
-                        || filename == "quicksave.ron")
-                {
-                    // Only include files that actually exist and have content
-                    if let Ok(metadata) = entry.metadata() {
-                        if metadata.len() > 0 {
-                            let modified_time = metadata
-                                .modified()
-                                .ok()
-                                .map(|time| {
-                                    let datetime = std::time::SystemTime::now()
-                                        .duration_since(time)
-                                        .map(|duration| {
-                                            let seconds_ago = duration.as_secs();
-                                            if seconds_ago < 60 {
-                                                "Just now".to_string()
-                                            } else if seconds_ago < 3600 {
-                                                format!("{} minutes ago", seconds_ago / 60)
-                                            } else if seconds_ago < 86400 {
-                                                format!("{} hours ago", seconds_ago / 3600)
-                                            } else {
-                                                format!("{} days ago", seconds_ago / 86400)
-                                            }
-                                        })
-                                        .unwrap_or_else(|_| "Recently".to_string());
-                                    datetime
-                                })
-                                .unwrap_or_else(|| "Unknown time".to_string());
-
-                            let display_name = if filename == "quicksave.ron" {
-                                "Quick Save".to_string()
-                            } else if filename.starts_with("autosave_") {
-                                // Extract turn number from autosave filename
-                                let parts: Vec<&str> = filename.split('_').collect();
-                                if parts.len() >= 3 {
-                                    format!("Autosave Turn {}", parts[2].replace(".ron", ""))
-                                } else {
-                                    filename.replace(".ron", "")
-                                }
+    for save in storage.list() {
+        let filename = save.key.as_str();
+        if is_save_key(filename) {
+            let modified_time = save
+                .modified
+                .map(|time| {
+                    web_time::SystemTime::now()
+                        .duration_since(time)
+                        .map(|duration| {
+                            let seconds_ago = duration.as_secs();
+                            if seconds_ago < 60 {
+                                "Just now".to_string()
+                            } else if seconds_ago < 3600 {
+                                format!("{} minutes ago", seconds_ago / 60)
+                            } else if seconds_ago < 86400 {
+                                format!("{} hours ago", seconds_ago / 3600)
                             } else {
-                                filename.replace(".ron", "").replace("save_", "Slot ")
-                            };
-
-                            save_files.push(SaveFileInfo {
-                                path: get_save_path(filename),
-                                display_name,
-                                timestamp: modified_time,
-                                turn: extract_turn_from_filename(filename),
-                            });
-                        }
-                    }
+                                format!("{} days ago", seconds_ago / 86400)
+                            }
+                        })
+                        .unwrap_or_else(|_| "Recently".to_string())
+                })
+                .unwrap_or_else(|| "Unknown time".to_string());
+
+            let display_name = if filename == QUICKSAVE_KEY {
+                "Quick Save".to_string()
+            } else if filename.starts_with("autosave_") {
+                // Extract turn number from autosave filename
+                let parts: Vec<&str> = filename.split('_').collect();
+                if parts.len() >= 3 {
+                    format!("Autosave Turn {}", parts[2].replace(".ron", ""))
+                } else {
+                    filename.replace(".ron", "")
                 }
-            }
+            } else {
+                filename.replace(".ron", "").replace("save_", "Slot ")
+            };
+
+            save_files.push(SaveFileInfo {
+                key: filename.to_string(),
+                display_name,
+                timestamp: modified_time,
+            });
         }
     }
 
     save_files.sort_by(|a, b| {
-        if a.path == "quicksave.ron" {
+        if a.key == QUICKSAVE_KEY {
             return std::cmp::Ordering::Less;
         }
-        if b.path == "quicksave.ron" {
+        if b.key == QUICKSAVE_KEY {
             return std::cmp::Ordering::Greater;
         }
-        if a.path.starts_with("autosave_") && !b.path.starts_with("autosave_") {
+        if a.key.starts_with("autosave_") && !b.key.starts_with("autosave_") {
             return std::cmp::Ordering::Less;
         }
-        if b.path.starts_with("autosave_") && !a.path.starts_with("autosave_") {
+        if b.key.starts_with("autosave_") && !a.key.starts_with("autosave_") {
             return std::cmp::Ordering::Greater;
         }
 
-        a.path.cmp(&b.path)
+        a.key.cmp(&b.key)
     });
     save_files
 }
 
-fn extract_turn_from_filename(_filename: &str) -> Option<u32> {
-    // Box Slayer doesn't have turns, but keep the function for compatibility
-    None
+/// Whether a storage key names a loadable save (as opposed to a backup or temporary file)
+fn is_save_key(key: &str) -> bool {
+    key.ends_with(".ron")
+        && (key.starts_with("save_") || key.starts_with("autosave_") || key == QUICKSAVE_KEY)
+}
+
+/// The most recently written save, if there is any
+pub fn latest_save(storage: &dyn storage::SaveStorage) -> Option<String> {
+    storage
+        .list()
+        .into_iter()
+        .filter(|save| is_save_key(&save.key))
+        .max_by_key(|save| save.modified)
+        .map(|save| save.key)
 }
 
 /// Handle save requests
@@ -267,100 +205,276 @@ fn handle_save_requests(
     save_request: Option<Res<SaveRequest>>,
     money: Res<crate::demo::player::shooting::Money>,
     upgrades: Res<crate::demo::shop::shop::PlayerUpgrades>,
+    stats: Res<GameStats>,
+    level: Res<State<CurrentLevel>>,
+    registry: Res<LevelRegistry>,
+    storage: Res<SaveBackend>,
+    mut toasts: EventWriter<Toast>,
 ) {
-    if let Some(request) = save_request {
-        if request.is_added() {
-            // Simple save implementation - just save the money and upgrades as JSON
-            let save_data = serde_json::json!({
-                "money": money.amount,
-                "upgrades": {
-                    "rapid_fire": upgrades.rapid_fire,
-                    "uzi": upgrades.uzi,
-                    "spread_shot": upgrades.spread_shot,
-                    "laser_beam": upgrades.laser_beam,
-                    "sniper": upgrades.sniper,
-                    "bazooka": upgrades.bazooka,
-                    "hammer": upgrades.hammer,
-                    "sword": upgrades.sword,
-                    "speed_boost": upgrades.speed_boost,
-                    "coin_magnet": upgrades.coin_magnet,
-                    "current_weapon": format!("{:?}", upgrades.current_weapon),
-                    "buffer_level": upgrades.buffer_level,
-                }
-            });
-
-            if let Err(e) = std::fs::write(&request.path, save_data.to_string()) {
-                error!("Failed to save game to {}: {}", request.path, e);
-            } else {
-                info!("Game saved to: {}", request.path);
-            }
-
-            commands.remove_resource::<SaveRequest>();
+    if let Some(request) = save_request
+        && request.is_added()
+    {
+        let level = registry.get(level.get().0);
+        let save_data = collect_save_data(&money, &upgrades, &stats, level);
+
+        if let Err(e) =
+            integrity::write_with_backup(&**storage, &request.key, &integrity::encode(&save_data))
+        {
+            error!("Failed to save game to {}: {}", request.key, e);
+            toasts.write(Toast::error("Save failed!"));
+        } else {
+            info!("Game saved to {}: {}", request.key, request.slot_name);
+            toasts.write(Toast::success("Game saved"));
         }
+
+        commands.remove_resource::<SaveRequest>();
     }
 }
 
+/// Simple save implementation - just save the money, upgrades and stats as JSON
+pub fn collect_save_data(
+    money: &crate::demo::player::shooting::Money,
+    upgrades: &crate::demo::shop::shop::PlayerUpgrades,
+    stats: &GameStats,
+    level: &LevelData,
+) -> serde_json::Value {
+    serde_json::json!({
+        "money": money.amount,
+        "upgrades": {
+            "rapid_fire": upgrades.rapid_fire,
+            "uzi": upgrades.uzi,
+            "spread_shot": upgrades.spread_shot,
+            "laser_beam": upgrades.laser_beam,
+            "sniper": upgrades.sniper,
+            "bazooka": upgrades.bazooka,
+            "hammer": upgrades.hammer,
+            "sword": upgrades.sword,
+            "speed_boost": upgrades.speed_boost,
+            "coin_magnet": upgrades.coin_magnet,
+            "current_weapon": format!("{:?}", upgrades.current_weapon),
+            "buffer_level": upgrades.buffer_level,
+            "sprint": upgrades.sprint,
+            "double_jump": upgrades.double_jump,
+            "dash": upgrades.dash,
+        },
+        "stats": stats,
+        "level": level.id,
+    })
+}
+
 /// Handle load requests
+///
+/// Loading from the title screen continues into gameplay through the loading screen.
 fn handle_load_requests(
     mut commands: Commands,
     load_request: Option<Res<LoadRequest>>,
     mut money: ResMut<crate::demo::player::shooting::Money>,
     mut upgrades: ResMut<crate::demo::shop::shop::PlayerUpgrades>,
+    mut stats: ResMut<GameStats>,
+    storage: Res<SaveBackend>,
+    mut toasts: EventWriter<Toast>,
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    level: Res<State<CurrentLevel>>,
+    mut next_level: ResMut<NextState<CurrentLevel>>,
+    registry: Res<LevelRegistry>,
 ) {
-    if let Some(request) = load_request {
-        if request.is_added() {
-            if let Ok(data) = std::fs::read_to_string(&request.path) {
-                if let Ok(save_data) = serde_json::from_str::<serde_json::Value>(&data) {
-                    // Load money
-                    if let Some(saved_money) = save_data["money"].as_u64() {
-                        money.amount = saved_money as u32;
-                    }
-
-                    // Load upgrades
-                    if let Some(saved_upgrades) = save_data["upgrades"].as_object() {
-                        upgrades.rapid_fire =
-                            saved_upgrades["rapid_fire"].as_bool().unwrap_or(false);
-                        upgrades.uzi = saved_upgrades["uzi"].as_bool().unwrap_or(false);
-                        upgrades.spread_shot =
-                            saved_upgrades["spread_shot"].as_bool().unwrap_or(false);
-                        upgrades.laser_beam =
-                            saved_upgrades["laser_beam"].as_bool().unwrap_or(false);
-                        upgrades.sniper = saved_upgrades["sniper"].as_bool().unwrap_or(false);
-                        upgrades.bazooka = saved_upgrades["bazooka"].as_bool().unwrap_or(false);
-                        upgrades.hammer = saved_upgrades["hammer"].as_bool().unwrap_or(false);
-                        upgrades.sword = saved_upgrades["sword"].as_bool().unwrap_or(false);
-                        upgrades.speed_boost =
-                            saved_upgrades["speed_boost"].as_u64().unwrap_or(0) as u32;
-                        upgrades.coin_magnet =
-                            saved_upgrades["coin_magnet"].as_bool().unwrap_or(false);
-                        upgrades.buffer_level =
-                            saved_upgrades["buffer_level"].as_u64().unwrap_or(1) as u32;
-
-                        // Parse weapon type
-                        if let Some(weapon_str) = saved_upgrades["current_weapon"].as_str() {
-                            upgrades.current_weapon = match weapon_str {
-                                "RapidFire" => crate::demo::shop::shop::WeaponType::RapidFire,
-                                "Uzi" => crate::demo::shop::shop::WeaponType::Uzi,
-                                "SpreadShot" => crate::demo::shop::shop::WeaponType::SpreadShot,
-                                "LaserBeam" => crate::demo::shop::shop::WeaponType::LaserBeam,
-                                "Sniper" => crate::demo::shop::shop::WeaponType::Sniper,
-                                "Bazooka" => crate::demo::shop::shop::WeaponType::Bazooka,
-                                "Hammer" => crate::demo::shop::shop::WeaponType::Hammer,
-                                "Sword" => crate::demo::shop::shop::WeaponType::Sword,
-                                _ => crate::demo::shop::shop::WeaponType::Normal,
-                            };
-                        }
-                    }
-
-                    info!("Game loaded from: {}", request.path);
-                } else {
-                    error!("Failed to parse save file: {}", request.path);
+    if let Some(request) = load_request
+        && request.is_added()
+    {
+        let save_data = match &*request {
+            LoadRequest::Slot(key) => read_slot(&**storage, key, &mut toasts),
+            LoadRequest::Import(code) => match integrity::import(code) {
+                Ok(save_data) => Some(save_data),
+                Err(e) => {
+                    warn!("Failed to import save code: {}", e);
+                    toasts.write(Toast::error("Invalid save code"));
+                    None
+                }
+            },
+        };
+
+        if let Some(save_data) = save_data {
+            apply_save_data(&save_data, &mut money, &mut upgrades, &mut stats);
+            toasts.write(Toast::success("Game loaded"));
+
+            let saved_level = saved_level(&save_data, &registry);
+            let level_changed = saved_level != *level.get();
+            next_level.set(saved_level);
+
+            // The level is respawned through the loading screen when it changes
+            if screen.get() == &Screen::Title || level_changed {
+                next_screen.set(Screen::Loading);
+            }
+        }
+
+        commands.remove_resource::<LoadRequest>();
+    }
+}
+
+/// The level a save was made in. Saves from before levels were added, or from a level that
+/// no longer exists, start in the first level.
+fn saved_level(save_data: &serde_json::Value, registry: &LevelRegistry) -> CurrentLevel {
+    let id = save_data["level"].as_str();
+    id.and_then(|id| registry.find(id)).unwrap_or_else(|| {
+        if let Some(id) = id {
+            warn!("Save is from unknown level {:?}, starting in the first", id);
+        }
+        CurrentLevel(0)
+    })
+}
+
+/// Read a save slot from storage.
+///
+/// If the save fails to read or verify, the slot's backup is tried instead and the player
+/// is told that an older save was restored.
+fn read_slot(
+    storage: &dyn storage::SaveStorage,
+    key: &str,
+    toasts: &mut EventWriter<Toast>,
+) -> Option<serde_json::Value> {
+    match integrity::read_verified(storage, key) {
+        Ok(save_data) => {
+            info!("Game loaded from: {}", key);
+            Some(save_data)
+        }
+        Err(e) => {
+            error!("Failed to load save file {}: {}", key, e);
+
+            let backup = integrity::backup_key(key);
+            match integrity::read_verified(storage, &backup) {
+                Ok(save_data) => {
+                    warn!("Restored backup save: {}", backup);
+                    toasts.write(Toast::error("Save was corrupted - restored from backup"));
+                    Some(save_data)
+                }
+                Err(backup_error) => {
+                    error!("Failed to load backup {}: {}", backup, backup_error);
+                    toasts.write(Toast::error(
+                        "Save is corrupted and no backup could be loaded",
+                    ));
+                    None
                 }
-            } else {
-                error!("Failed to read save file: {}", request.path);
             }
+        }
+    }
+}
+
+/// Copy the values of a decoded save into the game resources
+fn apply_save_data(
+    save_data: &serde_json::Value,
+    money: &mut crate::demo::player::shooting::Money,
+    upgrades: &mut crate::demo::shop::shop::PlayerUpgrades,
+    stats: &mut GameStats,
+) {
+    // Load money
+    if let Some(saved_money) = save_data["money"].as_u64() {
+        money.amount = saved_money as u32;
+    }
+
+    // Load upgrades. Indexing the value rather than the map reads missing keys as null, so
+    // saves from before an upgrade existed still load.
+    let saved_upgrades = &save_data["upgrades"];
+    if saved_upgrades.is_object() {
+        upgrades.rapid_fire = saved_upgrades["rapid_fire"].as_bool().unwrap_or(false);
+        upgrades.uzi = saved_upgrades["uzi"].as_bool().unwrap_or(false);
+        upgrades.spread_shot = saved_upgrades["spread_shot"].as_bool().unwrap_or(false);
+        upgrades.laser_beam = saved_upgrades["laser_beam"].as_bool().unwrap_or(false);
+        upgrades.sniper = saved_upgrades["sniper"].as_bool().unwrap_or(false);
+        upgrades.bazooka = saved_upgrades["bazooka"].as_bool().unwrap_or(false);
+        upgrades.hammer = saved_upgrades["hammer"].as_bool().unwrap_or(false);
+        upgrades.sword = saved_upgrades["sword"].as_bool().unwrap_or(false);
+        upgrades.speed_boost = saved_upgrades["speed_boost"].as_u64().unwrap_or(0) as u32;
+        upgrades.coin_magnet = saved_upgrades["coin_magnet"].as_bool().unwrap_or(false);
+        upgrades.buffer_level = saved_upgrades["buffer_level"].as_u64().unwrap_or(1) as u32;
+        upgrades.sprint = saved_upgrades["sprint"].as_bool().unwrap_or(false);
+        upgrades.double_jump = saved_upgrades["double_jump"].as_bool().unwrap_or(false);
+        upgrades.dash = saved_upgrades["dash"].as_bool().unwrap_or(false);
+
+        // Parse weapon type
+        if let Some(weapon_str) = saved_upgrades["current_weapon"].as_str() {
+            upgrades.current_weapon = crate::demo::shop::shop::WeaponType::from_string(weapon_str);
+        }
+    }
 
-            commands.remove_resource::<LoadRequest>();
+    // Saves from before stats were tracked start from zero
+    *stats = serde_json::from_value(save_data["stats"].clone()).unwrap_or_default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{
+        levels::LevelsConfig,
+        player::shooting::Money,
+        shop::shop::{PlayerUpgrades, WeaponType},
+    };
+
+    fn registry() -> LevelRegistry {
+        let level = |id: &str| LevelData {
+            id: id.to_string(),
+            ..default()
+        };
+        LevelRegistry {
+            config: LevelsConfig {
+                levels: vec![level("meadow"), level("desert")],
+            },
         }
     }
+
+    #[test]
+    fn level_round_trips_by_id() {
+        let registry = registry();
+        let save_data = collect_save_data(
+            &Money::default(),
+            &PlayerUpgrades::default(),
+            &GameStats::default(),
+            registry.get(1),
+        );
+        assert_eq!(save_data["level"], "desert");
+        assert_eq!(saved_level(&save_data, &registry), CurrentLevel(1));
+    }
+
+    #[test]
+    fn unknown_or_missing_level_starts_in_the_first() {
+        let registry = registry();
+        let unknown = serde_json::json!({ "level": "volcano" });
+        assert_eq!(saved_level(&unknown, &registry), CurrentLevel(0));
+        let missing = serde_json::json!({ "money": 10 });
+        assert_eq!(saved_level(&missing, &registry), CurrentLevel(0));
+    }
+
+    #[test]
+    fn loads_saves_without_ability_upgrades() {
+        let save_data = integrity::decode(include_str!("../../saves/quicksave.ron")).unwrap();
+        assert!(save_data["upgrades"].get("sprint").is_none());
+
+        let mut money = Money::default();
+        let mut upgrades = PlayerUpgrades {
+            dash: true,
+            ..default()
+        };
+        let mut stats = GameStats::default();
+        apply_save_data(&save_data, &mut money, &mut upgrades, &mut stats);
+
+        assert_eq!(money.amount, 900);
+        assert!(!upgrades.sprint && !upgrades.double_jump && !upgrades.dash);
+        assert_eq!(upgrades.buffer_level, 1);
+        assert_eq!(upgrades.current_weapon, WeaponType::Normal);
+    }
+
+    #[test]
+    fn imported_code_with_empty_upgrades_loads() {
+        let code = integrity::export(&serde_json::json!({ "money": 40, "upgrades": {} }));
+        let save_data = integrity::import(&code).unwrap();
+
+        let mut money = Money::default();
+        let mut upgrades = PlayerUpgrades::default();
+        let mut stats = GameStats::default();
+        apply_save_data(&save_data, &mut money, &mut upgrades, &mut stats);
+
+        assert_eq!(money.amount, 40);
+        assert_eq!(upgrades.speed_boost, 0);
+        assert_eq!(upgrades.buffer_level, 1);
+    }
 }
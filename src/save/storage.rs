@@ -0,0 +1,369 @@
+//! Where save files live.
+//!
+//! Save files are addressed by a key such as `save_001.ron`. The [`SaveStorage`] trait maps
+//! those keys onto the platform: a directory in the OS data folder on native builds,
+//! `localStorage` in the browser, and a plain map for tests.
+
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    sync::Mutex,
+};
+
+use bevy::prelude::*;
+// `std::time::SystemTime::now` panics on the web
+use web_time::SystemTime;
+
+/// A save file known to a [`SaveStorage`] backend.
+#[derive(Clone, Debug)]
+pub struct StoredSave {
+    pub key: String,
+    /// When the save was last written, if the backend can tell.
+    pub modified: Option<SystemTime>,
+}
+
+/// A place save files can be read from and written to.
+pub trait SaveStorage: Send + Sync + 'static {
+    /// Read the contents stored under `key`.
+    fn read(&self, key: &str) -> std::io::Result<String>;
+
+    /// Replace the contents stored under `key`. Implementations must never leave a partially
+    /// written value behind.
+    fn write(&self, key: &str, contents: &str) -> std::io::Result<()>;
+
+    /// Delete the value stored under `key`.
+    fn remove(&self, key: &str) -> std::io::Result<()>;
+
+    /// List every stored key.
+    fn list(&self) -> Vec<StoredSave>;
+
+    /// Whether anything is stored under `key`.
+    fn exists(&self, key: &str) -> bool {
+        self.list().iter().any(|save| save.key == key)
+    }
+}
+
+/// The storage backend used by the save system.
+#[derive(Resource)]
+pub struct SaveBackend(pub Box<dyn SaveStorage>);
+
+impl std::ops::Deref for SaveBackend {
+    type Target = dyn SaveStorage;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl SaveBackend {
+    /// The default backend for the platform the game was built for. Falls back to keeping
+    /// saves in memory if the platform storage can't be used.
+    pub fn platform_default() -> Self {
+        #[cfg(not(target_family = "wasm"))]
+        let storage = NativeStorage::new().map(|storage| Box::new(storage) as Box<dyn SaveStorage>);
+        #[cfg(target_family = "wasm")]
+        let storage = WebStorage::local_storage()
+            .map(|_| Box::new(WebStorage::default()) as Box<dyn SaveStorage>);
+
+        match storage {
+            Ok(storage) => Self(storage),
+            Err(e) => {
+                warn!("Save storage unavailable, progress will not persist: {}", e);
+                Self(Box::new(MemoryStorage::default()))
+            }
+        }
+    }
+}
+
+/// Stores saves as files in the OS data directory
+/// (`$XDG_DATA_HOME/WanderToKillABox/saves` on Linux).
+#[cfg(not(target_family = "wasm"))]
+pub struct NativeStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl NativeStorage {
+    /// Relative fallback used when the OS doesn't report a data directory.
+    const FALLBACK_DIR: &str = "saves";
+
+    pub fn new() -> std::io::Result<Self> {
+        let dir = dirs::data_dir()
+            .map(|dir| dir.join("WanderToKillABox").join("saves"))
+            .unwrap_or_else(|| {
+                warn!("No OS data directory found, saving next to the executable");
+                Self::FALLBACK_DIR.into()
+            });
+        Self::at(dir)
+    }
+
+    pub fn at(dir: impl Into<std::path::PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        info!("Saving to {}", dir.display());
+        Ok(Self { dir })
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl SaveStorage for NativeStorage {
+    fn read(&self, key: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(self.dir.join(key))
+    }
+
+    /// Write to `<key>.tmp`, flush it to disk and rename it over the real file.
+    fn write(&self, key: &str, contents: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let path = self.dir.join(key);
+        let tmp_path = self.dir.join(format!("{}.tmp", key));
+        {
+            let mut file = std::fs::File::create(&tmp_path)?;
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()?;
+        }
+        std::fs::rename(tmp_path, path)
+    }
+
+    fn remove(&self, key: &str) -> std::io::Result<()> {
+        std::fs::remove_file(self.dir.join(key))
+    }
+
+    fn list(&self) -> Vec<StoredSave> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() || metadata.len() == 0 {
+                    return None;
+                }
+                Some(StoredSave {
+                    key: entry.file_name().to_str()?.to_string(),
+                    modified: metadata.modified().ok(),
+                })
+            })
+            .collect()
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.dir.join(key).is_file()
+    }
+}
+
+/// Stores saves in the browser's `localStorage`, one entry per key. The time each key was
+/// written is kept in a second entry, since `localStorage` doesn't track it.
+#[cfg(target_family = "wasm")]
+pub struct WebStorage {
+    prefix: String,
+    modified_prefix: String,
+}
+
+#[cfg(target_family = "wasm")]
+impl Default for WebStorage {
+    fn default() -> Self {
+        Self {
+            prefix: "wander_to_kill_a_box/saves/".to_string(),
+            modified_prefix: "wander_to_kill_a_box/modified/".to_string(),
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl WebStorage {
+    fn local_storage() -> std::io::Result<web_sys::Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| Error::new(ErrorKind::Unsupported, "localStorage is not available"))
+    }
+
+    fn js_error(e: wasm_bindgen::JsValue) -> Error {
+        Error::other(format!("{:?}", e))
+    }
+
+    /// When `key` was last written, read from its timestamp entry.
+    fn modified(&self, storage: &web_sys::Storage, key: &str) -> Option<SystemTime> {
+        let millis = storage
+            .get_item(&format!("{}{}", self.modified_prefix, key))
+            .ok()
+            .flatten()?
+            .parse::<u64>()
+            .ok()?;
+        SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_millis(millis))
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl SaveStorage for WebStorage {
+    fn read(&self, key: &str) -> std::io::Result<String> {
+        Self::local_storage()?
+            .get_item(&format!("{}{}", self.prefix, key))
+            .map_err(Self::js_error)?
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no save named {}", key)))
+    }
+
+    /// A single `setItem` call either fully succeeds or leaves the old value in place.
+    fn write(&self, key: &str, contents: &str) -> std::io::Result<()> {
+        let storage = Self::local_storage()?;
+        storage
+            .set_item(&format!("{}{}", self.prefix, key), contents)
+            .map_err(Self::js_error)?;
+        storage
+            .set_item(
+                &format!("{}{}", self.modified_prefix, key),
+                &(js_sys::Date::now() as u64).to_string(),
+            )
+            .map_err(Self::js_error)
+    }
+
+    fn remove(&self, key: &str) -> std::io::Result<()> {
+        let storage = Self::local_storage()?;
+        storage
+            .remove_item(&format!("{}{}", self.modified_prefix, key))
+            .map_err(Self::js_error)?;
+        storage
+            .remove_item(&format!("{}{}", self.prefix, key))
+            .map_err(Self::js_error)
+    }
+
+    fn list(&self) -> Vec<StoredSave> {
+        let Ok(storage) = Self::local_storage() else {
+            return Vec::new();
+        };
+        let len = storage.length().unwrap_or(0);
+
+        (0..len)
+            .filter_map(|i| storage.key(i).ok().flatten())
+            .filter_map(|key| {
+                key.strip_prefix(&self.prefix).map(|key| StoredSave {
+                    key: key.to_string(),
+                    modified: self.modified(&storage, key),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Keeps saves in memory only. Used by tests, and when the platform storage is unavailable
+/// (e.g. a browser with storage disabled).
+#[derive(Default)]
+pub struct MemoryStorage {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl SaveStorage for MemoryStorage {
+    fn read(&self, key: &str) -> std::io::Result<String> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no save named {}", key)))
+    }
+
+    fn write(&self, key: &str, contents: &str) -> std::io::Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), contents.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> std::io::Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .remove(key)
+            .map(|_| ())
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no save named {}", key)))
+    }
+
+    fn list(&self) -> Vec<StoredSave> {
+        self.entries
+            .lock()
+            .unwrap()
+            .keys()
+            .map(|key| StoredSave {
+                key: key.clone(),
+                modified: None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::save::integrity::{SaveError, backup_key, encode, read_verified, write_with_backup};
+
+    #[test]
+    fn reads_back_what_was_written() {
+        let storage = MemoryStorage::default();
+        storage.write("save_001.ron", "first").unwrap();
+        storage.write("save_001.ron", "second").unwrap();
+
+        assert_eq!(storage.read("save_001.ron").unwrap(), "second");
+        assert_eq!(
+            storage.read("save_002.ron").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn rotates_previous_save_into_backup() {
+        let storage = MemoryStorage::default();
+        let first = json!({ "money": 10 });
+        let second = json!({ "money": 20 });
+        write_with_backup(&storage, "save_001.ron", &encode(&first)).unwrap();
+        assert!(!storage.exists(&backup_key("save_001.ron")));
+
+        write_with_backup(&storage, "save_001.ron", &encode(&second)).unwrap();
+        assert_eq!(read_verified(&storage, "save_001.ron").unwrap(), second);
+        assert_eq!(
+            read_verified(&storage, &backup_key("save_001.ron")).unwrap(),
+            first
+        );
+    }
+
+    #[test]
+    fn corrupt_slot_keeps_backup_to_fall_back_to() {
+        let storage = MemoryStorage::default();
+        let good = json!({ "money": 10 });
+        write_with_backup(&storage, "save_001.ron", &encode(&good)).unwrap();
+        write_with_backup(&storage, "save_001.ron", &encode(&good)).unwrap();
+
+        let corrupt = r#"{"checksum":1,"data":{"money":99}}"#;
+        storage.write("save_001.ron", corrupt).unwrap();
+        assert!(matches!(
+            read_verified(&storage, "save_001.ron"),
+            Err(SaveError::ChecksumMismatch { expected: 1, .. })
+        ));
+
+        // Saving over the corrupt slot must not rotate it into the backup
+        write_with_backup(&storage, "save_001.ron", &encode(&json!({ "money": 30 }))).unwrap();
+        assert_eq!(
+            read_verified(&storage, &backup_key("save_001.ron")).unwrap(),
+            good
+        );
+    }
+
+    #[test]
+    fn lists_stored_keys() {
+        let storage = MemoryStorage::default();
+        assert!(storage.list().is_empty());
+
+        storage.write("save_001.ron", "a").unwrap();
+        storage.write("save_002.ron", "b").unwrap();
+        storage.remove("save_001.ron").unwrap();
+
+        let keys: Vec<_> = storage.list().into_iter().map(|save| save.key).collect();
+        assert_eq!(keys, ["save_002.ron"]);
+        assert!(storage.exists("save_002.ron"));
+        assert!(!storage.exists("save_001.ron"));
+    }
+}
@@ -1,54 +1,57 @@
 use super::{
-    LoadRequest, SaveNotification, SaveNotifications, SaveRequest, get_save_files, get_save_path,
+    LoadRequest, QUICKSAVE_KEY, SaveRequest, clipboard, collect_save_data, get_save_files,
+    integrity, slot_key,
+    storage::{SaveBackend, SaveStorage},
+};
+use crate::{
+    controls::{ControlAction, Controls},
+    demo::{
+        levels::{CurrentLevel, LevelRegistry},
+        player::Money,
+        shop::shop::PlayerUpgrades,
+        stats::GameStats,
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{palette::*, widget},
+    toast::Toast,
+};
+use bevy::{
+    ecs::spawn::{SpawnIter, SpawnWith},
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+    ui::Val::*,
 };
-use crate::{menus::Menu, theme::widget};
-use bevy::prelude::*;
 
 /// Simple UI system for save/load functionality
 pub fn save_ui_system(
     mut commands: Commands,
-    keys: Res<ButtonInput<KeyCode>>,
-    mut save_notifications: ResMut<SaveNotifications>,
-    time: Res<Time>,
+    controls: Controls,
+    storage: Res<SaveBackend>,
+    mut toasts: EventWriter<Toast>,
 ) {
-    // Quick save with F5
-    if keys.just_pressed(KeyCode::F5) {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+    // Quick save, F5 by default
+    if controls.just_pressed(ControlAction::QuickSave) {
+        let timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
 
         commands.insert_resource(SaveRequest {
-            path: get_save_path("quicksave.ron"),
+            key: QUICKSAVE_KEY.to_string(),
             slot_name: format!("Quick Save - {}", timestamp),
         });
-
-        save_notifications.notifications.push(SaveNotification {
-            message: "Quick Save Created (F5)".to_string(),
-            timestamp: time.elapsed_secs_f64(),
-            duration: 2.0,
-        });
     }
 
-    // Quick load with F9
-    if keys.just_pressed(KeyCode::F9) {
-        let quicksave_path = get_save_path("quicksave.ron");
-        if std::path::Path::new(&quicksave_path).exists() {
-            commands.insert_resource(LoadRequest {
-                path: quicksave_path,
-            });
-
-            save_notifications.notifications.push(SaveNotification {
-                message: "Quick Load (F9)".to_string(),
-                timestamp: time.elapsed_secs_f64(),
-                duration: 2.0,
-            });
+    // Quick load, F9 by default
+    if controls.just_pressed(ControlAction::QuickLoad) {
+        if storage.exists(QUICKSAVE_KEY) {
+            commands.insert_resource(LoadRequest::Slot(QUICKSAVE_KEY.to_string()));
         } else {
-            save_notifications.notifications.push(SaveNotification {
-                message: "No Quick Save Found".to_string(),
-                timestamp: time.elapsed_secs_f64(),
-                duration: 2.0,
-            });
+            toasts.write(Toast::info("No Quick Save Found"));
         }
     }
 }
@@ -58,14 +61,13 @@ pub fn save_slot_button(slot_id: u32, label: &str) -> impl Bundle {
     widget::button(
         label,
         move |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
-            let filename = format!("save_{:03}.ron", slot_id);
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
+            let timestamp = web_time::SystemTime::now()
+                .duration_since(web_time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs();
 
             commands.insert_resource(SaveRequest {
-                path: get_save_path(&filename),
+                key: slot_key(slot_id),
                 slot_name: format!("Save Slot {} - {}", slot_id, timestamp),
             });
         },
@@ -73,13 +75,11 @@ pub fn save_slot_button(slot_id: u32, label: &str) -> impl Bundle {
 }
 
 /// Create a load game slot button
-pub fn load_slot_button(save_path: String, label: &str) -> impl Bundle {
+pub fn load_slot_button(save_key: String, label: impl Into<String>) -> impl Bundle {
     widget::button(
         label,
         move |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
-            commands.insert_resource(LoadRequest {
-                path: save_path.clone(),
-            });
+            commands.insert_resource(LoadRequest::Slot(save_key.clone()));
         },
     )
 }
@@ -89,72 +89,245 @@ pub fn load_slot_button(save_path: String, label: &str) -> impl Bundle {
 pub struct SaveLoadMenu;
 
 /// Spawn save/load menu UI
-pub fn spawn_save_load_menu(mut commands: Commands) {
-    commands.spawn((
-        widget::ui_root("Save/Load Menu"),
-        GlobalZIndex(3),
-        StateScoped(Menu::SaveLoad),
-        SaveLoadMenu,
-        children![
-            widget::header("Save / Load Game"),
-            // Save section
-            widget::label("Save Game:"),
-            save_slot_button(1, "Save Slot 1"),
-            save_slot_button(2, "Save Slot 2"),
-            save_slot_button(3, "Save Slot 3"),
-            // Quick save button
-            widget::button(
-                "Quick Save (F5)",
-                |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
-                    let timestamp = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs();
-
-                    commands.insert_resource(SaveRequest {
-                        path: get_save_path("quicksave.ron"),
-                        slot_name: format!("Quick Save - {}", timestamp),
-                    });
-                }
-            ),
-            // Load section header
-            widget::label("Load Game:"),
-            // Dynamic load buttons will be added here based on existing saves
-            load_buttons_container(),
+///
+/// Opened from the title screen there is nothing to save yet, so only the load section is shown.
+pub fn spawn_save_load_menu(
+    mut commands: Commands,
+    storage: Res<SaveBackend>,
+    screen: Res<State<Screen>>,
+) {
+    let in_gameplay = screen.get() == &Screen::Gameplay;
+
+    commands
+        .spawn((
+            widget::ui_root("Save/Load Menu"),
+            GlobalZIndex(3),
+            StateScoped(Menu::SaveLoad),
+            SaveLoadMenu,
+        ))
+        .with_children(|parent| {
+            if in_gameplay {
+                parent.spawn(widget::header("Save / Load Game"));
+                // Save section
+                parent.spawn(widget::label("Save Game:"));
+                parent.spawn(save_slot_button(1, "Save Slot 1"));
+                parent.spawn(save_slot_button(2, "Save Slot 2"));
+                parent.spawn(save_slot_button(3, "Save Slot 3"));
+                // Quick save button
+                parent.spawn(widget::button(
+                    "Quick Save (F5)",
+                    |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        let timestamp = web_time::SystemTime::now()
+                            .duration_since(web_time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs();
+
+                        commands.insert_resource(SaveRequest {
+                            key: QUICKSAVE_KEY.to_string(),
+                            slot_name: format!("Quick Save - {}", timestamp),
+                        });
+                    },
+                ));
+                parent.spawn(widget::button("Export Save Code", export_save_code));
+                // Load section header
+                parent.spawn(widget::label("Load Game:"));
+            } else {
+                parent.spawn(widget::header("Load Game"));
+            }
+            // One load button per existing save
+            parent.spawn(load_buttons_container(&**storage));
+            // Import section
+            parent.spawn(widget::label("Import Save Code (type or Ctrl+V):"));
+            parent.spawn(save_code_field());
+            parent.spawn(widget::button("Import", import_save_code));
             // Back button
-            widget::button(
-                "Back",
-                |_trigger: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>| {
-                    next_menu.set(Menu::Pause);
-                }
-            ),
-        ],
-    ));
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+fn go_back_on_click(
+    _: Trigger<Pointer<Click>>,
+    screen: Res<State<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_menu.set(if screen.get() == &Screen::Title {
+        Menu::Main
+    } else {
+        Menu::Pause
+    });
+}
+
+pub fn go_back(screen: Res<State<Screen>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(if screen.get() == &Screen::Title {
+        Menu::Main
+    } else {
+        Menu::Pause
+    });
 }
 
 /// Container for dynamically generated load buttons
-fn load_buttons_container() -> impl Bundle {
-    let save_files = get_save_files();
+fn load_buttons_container(storage: &dyn SaveStorage) -> impl Bundle {
+    let save_files = get_save_files(storage);
+    let no_saves = save_files.is_empty();
 
     (
         Name::new("Load Buttons Container"),
-        Node::default(),
-        children![
-            // Quick load button if quicksave exists
-            widget::button(
-                "Quick Load (F9)",
-                |_trigger: Trigger<Pointer<Click>>, mut commands: Commands| {
-                    commands.insert_resource(LoadRequest {
-                        path: get_save_path("quicksave.ron"),
-                    });
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(10.0),
+            ..default()
+        },
+        Children::spawn((
+            SpawnIter(save_files.into_iter().map(|save| {
+                load_slot_button(
+                    save.key,
+                    format!("{} ({})", save.display_name, save.timestamp),
+                )
+            })),
+            SpawnWith(move |parent: &mut ChildSpawner| {
+                // Show message if no saves
+                if no_saves {
+                    parent.spawn(widget::label("No saved games found"));
                 }
-            ),
-            // Show message if no saves
-            widget::label(if save_files.is_empty() {
-                "No saved games found"
-            } else {
-                "Save files available"
             }),
-        ],
+        )),
     )
 }
+
+/// Copy the current progress to the clipboard as a save code
+fn export_save_code(
+    _: Trigger<Pointer<Click>>,
+    money: Res<Money>,
+    upgrades: Res<PlayerUpgrades>,
+    stats: Res<GameStats>,
+    level: Res<State<CurrentLevel>>,
+    registry: Res<LevelRegistry>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let level = registry.get(level.get().0);
+    let code = integrity::export(&collect_save_data(&money, &upgrades, &stats, level));
+    info!("Save code: {}", code);
+
+    match clipboard::copy(&code) {
+        Ok(()) => {
+            toasts.write(Toast::success("Save code copied to clipboard"));
+        }
+        Err(e) => {
+            warn!("Failed to copy save code to clipboard: {}", e);
+            toasts.write(Toast::error(
+                "Couldn't access the clipboard - the save code was written to the log",
+            ));
+        }
+    }
+}
+
+/// Text field holding a save code to import
+#[derive(Component, Default)]
+pub struct SaveCodeField(String);
+
+#[derive(Component)]
+pub struct SaveCodeFieldText;
+
+/// How many trailing characters of a long save code are shown in the field
+const SAVE_CODE_PREVIEW_LEN: usize = 24;
+
+fn save_code_field() -> impl Bundle {
+    (
+        Name::new("Save Code Field"),
+        SaveCodeField::default(),
+        Node {
+            width: Px(380.0),
+            height: Px(40.0),
+            padding: UiRect::horizontal(Px(10.0)),
+            align_items: AlignItems::Center,
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(BUTTON_PRESSED_BACKGROUND),
+        BorderRadius::all(Px(6.0)),
+        children![(
+            Name::new("Save Code Field Text"),
+            SaveCodeFieldText,
+            Text::default(),
+            TextFont::from_font_size(20.0),
+            TextColor(BUTTON_TEXT),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+/// Type, erase and paste into the save code field while the save/load menu is open
+pub fn edit_save_code_field(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut field: Single<&mut SaveCodeField>,
+    mut commands: Commands,
+) {
+    let ctrl = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if ctrl {
+            if event.key_code == KeyCode::KeyV {
+                let pasted = clipboard::paste().unwrap_or_default();
+                field.0.extend(pasted.chars().filter(is_save_code_char));
+            }
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(text) => {
+                field.0.extend(text.chars().filter(is_save_code_char));
+            }
+            Key::Backspace => {
+                field.0.pop();
+            }
+            Key::Enter if !field.0.is_empty() => {
+                commands.insert_resource(LoadRequest::Import(field.0.clone()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Characters that can appear in a save code
+fn is_save_code_char(c: &char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Show the end of the typed save code, or a hint if the field is empty
+pub fn update_save_code_field_text(
+    field: Single<&SaveCodeField, Changed<SaveCodeField>>,
+    mut text: Single<&mut Text, With<SaveCodeFieldText>>,
+) {
+    let code = &field.0;
+    text.0 = if code.is_empty() {
+        "WTKB1...".to_string()
+    } else if code.chars().count() > SAVE_CODE_PREVIEW_LEN {
+        let tail: String = code.chars().rev().take(SAVE_CODE_PREVIEW_LEN).collect();
+        format!("...{}", tail.chars().rev().collect::<String>())
+    } else {
+        code.clone()
+    };
+}
+
+fn import_save_code(
+    _: Trigger<Pointer<Click>>,
+    field: Single<&SaveCodeField>,
+    mut commands: Commands,
+    mut toasts: EventWriter<Toast>,
+) {
+    if field.0.is_empty() {
+        toasts.write(Toast::info("Enter a save code first"));
+        return;
+    }
+    commands.insert_resource(LoadRequest::Import(field.0.clone()));
+}
@@ -2,24 +2,38 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 
-use crate::{Pause, demo::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    controls::{ControlAction, control_just_pressed},
+    demo::{level::spawn_level, player::movement::block_input_while_spawned},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
 
+    // The overlay stays up in every menu opened from the pause menu.
+    block_input_while_spawned::<PauseOverlay>(app);
+
     // Toggle pause on key press.
     app.add_systems(
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::Gameplay)
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                in_state(Screen::Gameplay).and(in_state(Menu::None)).and(
+                    control_just_pressed(ControlAction::Pause)
+                        .or(input_just_pressed(KeyCode::Escape)),
+                ),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    // P can be typed into the save code field.
+                    .and(not(in_state(Menu::SaveLoad)))
+                    // Keys pressed there are captured for rebinding.
+                    .and(not(in_state(Menu::Controls)))
+                    .and(control_just_pressed(ControlAction::Pause)),
             ),
         ),
     );
@@ -38,9 +52,13 @@ fn pause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(true));
 }
 
+#[derive(Component)]
+struct PauseOverlay;
+
 fn spawn_pause_overlay(mut commands: Commands) {
     commands.spawn((
         Name::new("Pause Overlay"),
+        PauseOverlay,
         Node {
             width: Percent(100.0),
             height: Percent(100.0),
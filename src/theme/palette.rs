@@ -14,3 +14,53 @@ pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 /// #3d4999
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+
+/// #000000b3
+pub const TOAST_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+/// #ececec
+pub const TOAST_INFO: Color = Color::srgb(0.925, 0.925, 0.925);
+/// #7fd97f
+pub const TOAST_SUCCESS: Color = Color::srgb(0.498, 0.851, 0.498);
+/// #f26666
+pub const TOAST_ERROR: Color = Color::srgb(0.949, 0.400, 0.400);
+
+/// #f2c94c
+pub const ACHIEVEMENT_BORDER: Color = Color::srgb(0.949, 0.788, 0.298);
+
+/// #ffffff
+pub const HUD_TEXT: Color = Color::WHITE;
+/// #cc3333
+pub const HUD_TIMER_TEXT: Color = Color::srgb(0.8, 0.2, 0.2);
+/// #4d4d4d
+pub const BUFFER_BAR_BACKGROUND: Color = Color::srgb(0.3, 0.3, 0.3);
+/// #00cc33
+pub const BUFFER_BAR_FILL: Color = Color::srgb(0.0, 0.8, 0.2);
+
+/// #000000a6
+pub const WEAPON_SLOT_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.65);
+/// #666666
+pub const WEAPON_SLOT_BORDER: Color = Color::srgb(0.4, 0.4, 0.4);
+/// #fcfbcc
+pub const WEAPON_SLOT_ACTIVE: Color = HEADER_TEXT;
+/// #00ffff
+pub const WEAPON_SLOT_FIRING: Color = Color::srgb(0.0, 1.0, 1.0);
+/// #000000b3
+pub const WEAPON_SLOT_COOLDOWN: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+
+/// #ffffff
+pub const FLOATING_DAMAGE_TEXT: Color = Color::WHITE;
+/// #ffd94d
+pub const FLOATING_COIN_TEXT: Color = Color::srgb(1.0, 0.851, 0.302);
+
+/// #b3b3b3
+pub const INCOME_TEXT: Color = Color::srgb(0.7, 0.7, 0.7);
+/// #f26666
+pub const MONEY_SPENT_FLASH: Color = TOAST_ERROR;
+
+/// #000000b3
+pub const HEALTH_BAR_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+/// #e64d4d
+pub const HEALTH_BAR_FILL: Color = Color::srgb(0.902, 0.302, 0.302);
+
+/// #f26666
+pub const BINDING_CONFLICT_TEXT: Color = TOAST_ERROR;
@@ -0,0 +1,173 @@
+//! Short on-screen notifications ("toasts") stacked in the top-right corner.
+//!
+//! Anything that wants to tell the player something writes a [`Toast`] event:
+//!
+//! ```ignore
+//! fn on_save(mut toasts: EventWriter<Toast>) {
+//!     toasts.write(Toast::success("Game saved"));
+//! }
+//! ```
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::theme::palette::{TOAST_BACKGROUND, TOAST_ERROR, TOAST_INFO, TOAST_SUCCESS};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<Toast>();
+    app.register_type::<ToastLifetime>();
+
+    app.add_systems(Startup, spawn_toast_stack);
+    app.add_systems(Update, (spawn_toasts, fade_toasts).chain());
+}
+
+/// The maximum number of toasts on screen at once. Older toasts are dropped first.
+const MAX_TOASTS: usize = 5;
+/// How long a toast takes to fade out at the end of its lifetime, in seconds.
+const FADE_DURATION_SECS: f32 = 0.5;
+/// How long a toast stays on screen unless specified otherwise, in seconds.
+const DEFAULT_DURATION_SECS: f32 = 2.5;
+
+/// Request to show a notification to the player.
+#[derive(Event, Clone, Debug)]
+pub struct Toast {
+    pub message: String,
+    pub kind: ToastKind,
+    /// Seconds the toast stays on screen, including its fade-out.
+    pub duration: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Error,
+}
+
+impl Toast {
+    pub fn new(kind: ToastKind, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            kind,
+            duration: DEFAULT_DURATION_SECS,
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Info, message)
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Success, message)
+    }
+
+    /// Errors stay on screen a little longer than other toasts.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Error, message).with_duration(4.0)
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+impl ToastKind {
+    fn text_color(self) -> Color {
+        match self {
+            ToastKind::Info => TOAST_INFO,
+            ToastKind::Success => TOAST_SUCCESS,
+            ToastKind::Error => TOAST_ERROR,
+        }
+    }
+}
+
+/// The column in the corner of the screen that holds all toasts.
+#[derive(Component)]
+struct ToastStack;
+
+/// Time left before a toast disappears.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ToastLifetime(Timer);
+
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Toast Stack"),
+        ToastStack,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(16.0),
+            right: Px(16.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Px(8.0),
+            ..default()
+        },
+        // Above menus and the pause overlay.
+        GlobalZIndex(10),
+        Pickable::IGNORE,
+    ));
+}
+
+fn spawn_toasts(
+    mut commands: Commands,
+    mut toasts: EventReader<Toast>,
+    stack: Single<(Entity, Option<&Children>), With<ToastStack>>,
+) {
+    let (stack, existing) = *stack;
+    let existing: &[Entity] = existing.map_or(&[][..], |children| &children[..]);
+    let incoming: Vec<&Toast> = toasts.read().collect();
+
+    // Make room by dropping the oldest toasts, which sit at the top of the stack.
+    let overflow = (existing.len() + incoming.len()).saturating_sub(MAX_TOASTS);
+    for &oldest in existing.iter().take(overflow) {
+        commands.entity(oldest).despawn();
+    }
+
+    for toast in incoming
+        .into_iter()
+        .skip(overflow.saturating_sub(existing.len()))
+    {
+        commands.entity(stack).with_child((
+            Name::new("Toast"),
+            ToastLifetime(Timer::from_seconds(toast.duration, TimerMode::Once)),
+            Node {
+                padding: UiRect::axes(Px(16.0), Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(TOAST_BACKGROUND),
+            BorderRadius::all(Px(6.0)),
+            Pickable::IGNORE,
+            children![(
+                Name::new("Toast Text"),
+                Text(toast.message.clone()),
+                TextFont::from_font_size(20.0),
+                TextColor(toast.kind.text_color()),
+                Pickable::IGNORE,
+            )],
+        ));
+    }
+}
+
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut ToastLifetime, &mut BackgroundColor, &Children)>,
+    mut text_query: Query<&mut TextColor>,
+) {
+    for (entity, mut lifetime, mut background, children) in &mut toast_query {
+        lifetime.0.tick(time.delta());
+        if lifetime.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let alpha = (lifetime.0.remaining_secs() / FADE_DURATION_SECS).min(1.0);
+        background.0 = TOAST_BACKGROUND.with_alpha(TOAST_BACKGROUND.alpha() * alpha);
+        for &child in children {
+            if let Ok(mut text_color) = text_query.get_mut(child) {
+                text_color.0.set_alpha(alpha);
+            }
+        }
+    }
+}